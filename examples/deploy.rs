@@ -6,7 +6,8 @@ use rs_builder_relayer_client::RelayClient;
 use std::env;
 use uuid::Uuid;
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
     dotenv().ok();
     env_logger::init();
 
@@ -33,11 +34,11 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let client = RelayClient::new(&relayer_url, chain_id, Some(&pk), Some(builder_config))?;
 
-    let resp = client.deploy()?;
+    let resp = client.deploy().await?;
     println!("Deploy response: {:?}", resp);
 
-    let awaited_txn = resp.wait()?;
-    println!("Awaited transaction: {:?}", awaited_txn);
+    let outcome = resp.wait().await?;
+    println!("Outcome: {:?}", outcome);
 
     Ok(())
 }
@@ -0,0 +1,328 @@
+//! Blocking facade over the async [`crate::RelayClient`].
+//!
+//! Every method drives the async implementation to completion on a
+//! current-thread tokio runtime owned by the client. Do not use this from
+//! inside an async context; use [`crate::RelayClient`] directly there.
+
+use crate::client::RelayClient as AsyncRelayClient;
+use crate::config::ContractConfig;
+use crate::errors::{RelayerApiException, RelayerClientException};
+use crate::models::{
+    NonceResponse, ProxyTransaction, RelayPayload, RelayerTransaction, SafeGasParams,
+    SafeTransaction, TransactionRequest,
+};
+use crate::response::ClientRelayerTransactionResponse as AsyncResponse;
+use crate::retry::RetryPolicy;
+use crate::transport::RelayTransport;
+use crate::watch::{TransactionOutcome, WatchOptions};
+use alloy::signers::Signer;
+use ethers::types::Address;
+use polymarket_client_sdk::auth::builder::Builder;
+use std::fmt::{Debug, Error, Formatter};
+use std::time::Duration;
+use tokio::runtime::{Builder as RuntimeBuilder, Runtime};
+
+pub struct RelayClient {
+    inner: AsyncRelayClient,
+    runtime: Runtime,
+}
+
+impl RelayClient {
+    pub fn new(
+        relayer_url: &str,
+        chain_id: u64,
+        private_key: Option<&str>,
+        builder_config: Option<Builder>,
+    ) -> Result<Self, RelayerClientException> {
+        let inner = AsyncRelayClient::new(relayer_url, chain_id, private_key, builder_config)?;
+        Self::from_async(inner)
+    }
+
+    /// Like [`RelayClient::new`], with custom contracts for `chain_id`.
+    pub fn new_with_contract_config(
+        relayer_url: &str,
+        chain_id: u64,
+        contract_config: ContractConfig,
+        private_key: Option<&str>,
+        builder_config: Option<Builder>,
+    ) -> Result<Self, RelayerClientException> {
+        let inner = AsyncRelayClient::new_with_contract_config(
+            relayer_url,
+            chain_id,
+            contract_config,
+            private_key,
+            builder_config,
+        )?;
+        Self::from_async(inner)
+    }
+
+    fn from_async(inner: AsyncRelayClient) -> Result<Self, RelayerClientException> {
+        let runtime = RuntimeBuilder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(RelayerClientException::Runtime)?;
+        Ok(RelayClient { inner, runtime })
+    }
+
+    /// Replaces the HTTP transport of the wrapped client.
+    pub fn with_transport(mut self, transport: impl RelayTransport + 'static) -> Self {
+        self.inner = self.inner.with_transport(transport);
+        self
+    }
+
+    /// Replaces the signer of the wrapped client.
+    pub fn with_signer(mut self, signer: impl Signer + Send + Sync + 'static) -> Self {
+        self.inner = self.inner.with_signer(signer);
+        self
+    }
+
+    /// Replaces the retry policy of the wrapped client.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.inner = self.inner.with_retry_policy(retry_policy);
+        self
+    }
+
+    /// Enables local signature verification in [`RelayClient::execute`].
+    pub fn with_signature_verification(mut self, enabled: bool) -> Self {
+        self.inner = self.inner.with_signature_verification(enabled);
+        self
+    }
+
+    /// Sets the nonce resync interval of the wrapped client.
+    pub fn with_nonce_resync_interval(mut self, interval: Option<Duration>) -> Self {
+        self.inner = self.inner.with_nonce_resync_interval(interval);
+        self
+    }
+
+    /// The async client this facade drives.
+    pub fn inner(&self) -> &AsyncRelayClient {
+        &self.inner
+    }
+
+    pub fn get_nonce(
+        &self,
+        signer_address: &Address,
+        signer_type: &str,
+    ) -> Result<NonceResponse, RelayerApiException> {
+        self.runtime
+            .block_on(self.inner.get_nonce(signer_address, signer_type))
+    }
+
+    pub fn get_relay_payload(
+        &self,
+        signer_address: &Address,
+        signer_type: &str,
+    ) -> Result<RelayPayload, RelayerApiException> {
+        self.runtime
+            .block_on(self.inner.get_relay_payload(signer_address, signer_type))
+    }
+
+    pub fn get_transaction(
+        &self,
+        transaction_id: &str,
+    ) -> Result<Vec<RelayerTransaction>, RelayerApiException> {
+        self.runtime
+            .block_on(self.inner.get_transaction(transaction_id))
+    }
+
+    pub fn get_transactions(&self) -> Result<Vec<RelayerTransaction>, RelayerApiException> {
+        self.runtime.block_on(self.inner.get_transactions())
+    }
+
+    pub fn get_deployed(&self, safe_address: &Address) -> Result<bool, RelayerApiException> {
+        self.runtime.block_on(self.inner.get_deployed(safe_address))
+    }
+
+    pub fn execute(
+        &self,
+        transactions: &[SafeTransaction],
+        metadata: Option<&str>,
+        gas_params: Option<&SafeGasParams>,
+    ) -> Result<ClientRelayerTransactionResponse<'_>, RelayerClientException> {
+        let resp = self
+            .runtime
+            .block_on(self.inner.execute(transactions, metadata, gas_params))?;
+        Ok(ClientRelayerTransactionResponse::from_async(resp, self))
+    }
+
+    pub fn setup_trading_approvals(
+        &self,
+    ) -> Result<ClientRelayerTransactionResponse<'_>, RelayerClientException> {
+        let resp = self
+            .runtime
+            .block_on(self.inner.setup_trading_approvals())?;
+        Ok(ClientRelayerTransactionResponse::from_async(resp, self))
+    }
+
+    pub fn deploy(&self) -> Result<ClientRelayerTransactionResponse<'_>, RelayerClientException> {
+        let resp = self.runtime.block_on(self.inner.deploy())?;
+        Ok(ClientRelayerTransactionResponse::from_async(resp, self))
+    }
+
+    pub fn deploy_with_payment(
+        &self,
+        payment_token: &Address,
+        payment: &str,
+        payment_receiver: &Address,
+    ) -> Result<ClientRelayerTransactionResponse<'_>, RelayerClientException> {
+        let resp = self.runtime.block_on(self.inner.deploy_with_payment(
+            payment_token,
+            payment,
+            payment_receiver,
+        ))?;
+        Ok(ClientRelayerTransactionResponse::from_async(resp, self))
+    }
+
+    pub fn execute_proxy(
+        &self,
+        transactions: &[ProxyTransaction],
+        metadata: Option<&str>,
+    ) -> Result<ClientRelayerTransactionResponse<'_>, RelayerClientException> {
+        let resp = self
+            .runtime
+            .block_on(self.inner.execute_proxy(transactions, metadata))?;
+        Ok(ClientRelayerTransactionResponse::from_async(resp, self))
+    }
+
+    pub fn build_execute_request(
+        &self,
+        transactions: &[SafeTransaction],
+        nonce: &str,
+        metadata: Option<&str>,
+        gas_params: Option<&SafeGasParams>,
+    ) -> Result<TransactionRequest, RelayerClientException> {
+        self.runtime.block_on(self.inner.build_execute_request(
+            transactions,
+            nonce,
+            metadata,
+            gas_params,
+        ))
+    }
+
+    pub fn build_deploy_request(&self) -> Result<TransactionRequest, RelayerClientException> {
+        self.runtime.block_on(self.inner.build_deploy_request())
+    }
+
+    pub fn build_deploy_request_with_payment(
+        &self,
+        payment_token: &Address,
+        payment: &str,
+        payment_receiver: &Address,
+    ) -> Result<TransactionRequest, RelayerClientException> {
+        self.runtime
+            .block_on(self.inner.build_deploy_request_with_payment(
+                payment_token,
+                payment,
+                payment_receiver,
+            ))
+    }
+
+    pub fn build_proxy_request(
+        &self,
+        transactions: &[ProxyTransaction],
+        relay_payload: &RelayPayload,
+        metadata: Option<&str>,
+    ) -> Result<TransactionRequest, RelayerClientException> {
+        self.runtime.block_on(
+            self.inner
+                .build_proxy_request(transactions, relay_payload, metadata),
+        )
+    }
+
+    pub fn submit_signed(
+        &self,
+        request: &TransactionRequest,
+    ) -> Result<ClientRelayerTransactionResponse<'_>, RelayerClientException> {
+        let resp = self.runtime.block_on(self.inner.submit_signed(request))?;
+        Ok(ClientRelayerTransactionResponse::from_async(resp, self))
+    }
+
+    pub fn poll_until_state(
+        &self,
+        transaction_id: &str,
+        states: &[&str],
+        fail_state: Option<&str>,
+        max_polls: Option<usize>,
+        poll_frequency: Option<u64>,
+    ) -> Result<Option<RelayerTransaction>, RelayerApiException> {
+        self.runtime.block_on(self.inner.poll_until_state(
+            transaction_id,
+            states,
+            fail_state,
+            max_polls,
+            poll_frequency,
+        ))
+    }
+
+    /// Blocks until the typed outcome of `transaction_id` is known.
+    pub fn wait_for_outcome(
+        &self,
+        transaction_id: &str,
+        options: WatchOptions,
+    ) -> Result<TransactionOutcome, RelayerApiException> {
+        self.runtime
+            .block_on(self.inner.wait_for_outcome(transaction_id, options))
+    }
+
+    pub fn get_expected_safe(&self) -> Result<Address, RelayerClientException> {
+        self.inner.get_expected_safe()
+    }
+
+    pub fn get_expected_proxy_wallet(&self) -> Result<Address, RelayerClientException> {
+        self.inner.get_expected_proxy_wallet()
+    }
+}
+
+pub struct ClientRelayerTransactionResponse<'a> {
+    pub transaction_id: Option<String>,
+    pub transaction_hash: Option<String>,
+    pub hash: Option<String>,
+    pub client: &'a RelayClient,
+}
+
+impl<'a> ClientRelayerTransactionResponse<'a> {
+    fn from_async(resp: AsyncResponse<'_>, client: &'a RelayClient) -> Self {
+        ClientRelayerTransactionResponse {
+            transaction_id: resp.transaction_id,
+            transaction_hash: resp.transaction_hash,
+            hash: resp.hash,
+            client,
+        }
+    }
+
+    pub fn get_transaction(&self) -> Result<Vec<RelayerTransaction>, RelayerApiException> {
+        if let Some(ref id) = self.transaction_id {
+            self.client.get_transaction(id)
+        } else {
+            Err(RelayerApiException::MissingTransactionId)
+        }
+    }
+
+    /// Blocks up to a minute for the transaction to be mined, fail or be
+    /// invalidated; `wait_for_outcome` with the default [`WatchOptions`].
+    pub fn wait(&self) -> Result<TransactionOutcome, RelayerApiException> {
+        self.wait_for_outcome(WatchOptions::default())
+    }
+
+    /// Blocks until the typed outcome of the transaction is known.
+    pub fn wait_for_outcome(
+        &self,
+        options: WatchOptions,
+    ) -> Result<TransactionOutcome, RelayerApiException> {
+        let Some(ref transaction_id) = self.transaction_id else {
+            return Err(RelayerApiException::MissingTransactionId);
+        };
+
+        self.client.wait_for_outcome(transaction_id, options)
+    }
+}
+
+impl Debug for ClientRelayerTransactionResponse<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        write!(
+            f,
+            "ClientRelayerTransactionResponse {{ transaction_id: {:?}, transaction_hash: {:?}, hash: {:?} }}",
+            self.transaction_id, self.transaction_hash, self.hash
+        )
+    }
+}
@@ -1,95 +1,101 @@
-use crate::builder::derive::derive;
 use crate::config::ContractConfig;
-use crate::constants::SAFE_FACTORY_NAME;
-use crate::model::base::BaseEIP712Model;
+use crate::conversion::ToAlloy;
+use crate::errors::RelayerClientException;
 use crate::model::create_proxy::CreateProxy;
 use crate::models::{
     SafeCreateTransactionArgs, SignatureParams, TransactionRequest, TransactionType,
 };
-use alloy::signers::local::PrivateKeySigner;
-use alloy_primitives::B256;
-use alloy_signer::SignerSync;
+use crate::utils::hash_from_hex;
+use alloy::dyn_abi::Eip712Domain;
+use alloy::sol_types::SolStruct;
+use alloy_primitives::{B256, Signature};
+use alloy_signer::{Signer, SignerSync};
 use ethers::types::{Address, U256};
+use std::str::FromStr;
 
-fn make_domain(name: &str, verifying_contract: &Address, chain_id: u64) -> Vec<u8> {
-    // Simplified domain encoding
-    let mut domain = Vec::new();
-    domain.extend_from_slice(name.as_bytes());
-    domain.extend_from_slice(verifying_contract.as_bytes());
-    domain.extend_from_slice(&chain_id.to_be_bytes());
-    domain
+/// `EIP712Domain(string name,uint256 chainId,address verifyingContract)` of the Safe factory.
+fn make_domain(name: &str, verifying_contract: &Address, chain_id: u64) -> Eip712Domain {
+    Eip712Domain {
+        name: Some(name.to_string().into()),
+        chain_id: Some(chain_id.to_alloy()),
+        verifying_contract: Some(verifying_contract.to_alloy()),
+        ..Eip712Domain::default()
+    }
 }
 
 pub fn create_safe_create_struct_hash(
-    safe_factory: &Address,
+    config: &ContractConfig,
     chain_id: u64,
     payment_token: &Address,
     payment: &str,
     payment_receiver: &Address,
-) -> Result<String, String> {
-    let payment_u256 =
-        U256::from_dec_str(payment).map_err(|e| format!("Invalid payment: {}", e))?;
+) -> Result<String, RelayerClientException> {
+    let payment_u256 = U256::from_dec_str(payment)
+        .map_err(|e| RelayerClientException::encoding(format!("Invalid payment: {}", e)))?;
 
     let create_proxy = CreateProxy {
-        payment_token: *payment_token,
-        payment: payment_u256,
-        payment_receiver: *payment_receiver,
+        paymentToken: payment_token.to_alloy(),
+        payment: payment_u256.to_alloy(),
+        paymentReceiver: payment_receiver.to_alloy(),
     };
 
-    let domain = make_domain(SAFE_FACTORY_NAME, safe_factory, chain_id);
-    Ok(create_proxy.generate_struct_hash(&domain))
+    let domain = make_domain(&config.safe_factory_name, &config.safe_factory, chain_id);
+    Ok(create_proxy.eip712_signing_hash(&domain).to_string())
 }
 
-pub fn create_safe_create_signature(
-    signer: &PrivateKeySigner,
-    safe_factory: &Address,
+pub async fn create_safe_create_signature<S: Signer + Send + Sync + ?Sized>(
+    signer: &S,
+    config: &ContractConfig,
     chain_id: u64,
     payment_token: &Address,
     payment: &str,
     payment_receiver: &Address,
-) -> Result<String, String> {
-    let struct_hash = create_safe_create_struct_hash(
-        safe_factory,
-        chain_id,
-        payment_token,
-        payment,
-        payment_receiver,
-    )?;
+) -> Result<String, RelayerClientException> {
+    let struct_hash =
+        create_safe_create_struct_hash(config, chain_id, payment_token, payment, payment_receiver)?;
+    let hash_array = hash_from_hex(&struct_hash)?;
 
-    let hash = if struct_hash.starts_with("0x") {
-        hex::decode(&struct_hash[2..])
-    } else {
-        hex::decode(&struct_hash)
-    }
-    .map_err(|e| format!("Invalid hash: {}", e))?;
+    // The EIP-712 digest is signed directly, without the eth_sign prefix
+    signer
+        .sign_hash(&B256::from(hash_array))
+        .await
+        .map_err(RelayerClientException::Signing)
+        .map(|sig| sig.to_string())
+}
 
-    if hash.len() != 32 {
-        return Err("Invalid hash length".to_string());
-    }
+pub fn create_safe_create_signature_sync<S: SignerSync + ?Sized>(
+    signer: &S,
+    config: &ContractConfig,
+    chain_id: u64,
+    payment_token: &Address,
+    payment: &str,
+    payment_receiver: &Address,
+) -> Result<String, RelayerClientException> {
+    let struct_hash =
+        create_safe_create_struct_hash(config, chain_id, payment_token, payment, payment_receiver)?;
+    let hash_array = hash_from_hex(&struct_hash)?;
 
-    let hash_array: [u8; 32] = hash.try_into().unwrap();
     signer
-        .sign_hash_sync(&B256::from_slice(&hash_array))
-        .map_err(|e| format!("Signing failed: {}", e))
+        .sign_hash_sync(&B256::from(hash_array))
+        .map_err(RelayerClientException::Signing)
         .map(|sig| sig.to_string())
 }
 
-pub fn build_safe_create_transaction_request(
-    signer: &PrivateKeySigner,
+/// Builds the SAFE-CREATE request with an empty `signature`.
+fn prepare_safe_create_transaction_request(
     args: &SafeCreateTransactionArgs,
     config: &ContractConfig,
-) -> Result<TransactionRequest, String> {
-    let factory = config.safe_factory;
-    let safe_address = derive(&args.from_address, &factory);
+) -> Result<TransactionRequest, RelayerClientException> {
+    let payment = U256::from_dec_str(&args.payment)
+        .map_err(|e| RelayerClientException::encoding(format!("Invalid payment: {}", e)))?;
+    if !payment.is_zero() && args.payment_token.is_zero() {
+        return Err(RelayerClientException::encoding(
+            "payment_token must be set when payment is nonzero",
+        ));
+    }
 
-    let sig = create_safe_create_signature(
-        signer,
-        &factory,
-        args.chain_id,
-        &args.payment_token,
-        &args.payment,
-        &args.payment_receiver,
-    )?;
+    let factory = config.safe_factory;
+    let safe_address = config.safe_address(&args.from_address)?;
 
     let sig_params = SignatureParams {
         gas_price: None,
@@ -98,21 +104,228 @@ pub fn build_safe_create_transaction_request(
         base_gas: None,
         gas_token: None,
         refund_receiver: None,
-        payment_token: Some(args.payment_token.to_string()),
-        payment: Some(args.payment.clone()),
-        payment_receiver: Some(args.payment_receiver.to_string()),
+        payment_token: Some(format!("{}", args.payment_token.to_alloy())), // Display trait provides checksummed format
+        payment: Some(payment.to_string()),
+        payment_receiver: Some(format!("{}", args.payment_receiver.to_alloy())),
+        gas_limit: None,
+        relayer_fee: None,
+        relay_hub: None,
+        relay: None,
     };
 
     Ok(TransactionRequest {
         transaction_type: TransactionType::SafeCreate.as_str().to_string(),
-        from_address: args.from_address.to_string(),
-        to: factory.to_string(),
-        proxy: safe_address.to_string(),
+        from_address: format!("{}", args.from_address.to_alloy()),
+        to: format!("{}", factory.to_alloy()),
+        proxy: format!("{}", safe_address.to_alloy()),
         data: "0x".to_string(),
-        signature: sig,
+        signature: String::new(),
         signature_params: sig_params,
         value: None,
         nonce: None,
         metadata: None,
     })
 }
+
+pub async fn build_safe_create_transaction_request<S: Signer + Send + Sync + ?Sized>(
+    signer: &S,
+    args: &SafeCreateTransactionArgs,
+    config: &ContractConfig,
+) -> Result<TransactionRequest, RelayerClientException> {
+    let mut request = prepare_safe_create_transaction_request(args, config)?;
+    request.signature = create_safe_create_signature(
+        signer,
+        config,
+        args.chain_id,
+        &args.payment_token,
+        &args.payment,
+        &args.payment_receiver,
+    )
+    .await?;
+    Ok(request)
+}
+
+pub fn build_safe_create_transaction_request_sync<S: SignerSync + ?Sized>(
+    signer: &S,
+    args: &SafeCreateTransactionArgs,
+    config: &ContractConfig,
+) -> Result<TransactionRequest, RelayerClientException> {
+    let mut request = prepare_safe_create_transaction_request(args, config)?;
+    request.signature = create_safe_create_signature_sync(
+        signer,
+        config,
+        args.chain_id,
+        &args.payment_token,
+        &args.payment,
+        &args.payment_receiver,
+    )?;
+    Ok(request)
+}
+
+/// Rebuilds the `CreateProxy` hash from a signed SAFE-CREATE request and
+/// checks that it is addressed to `config`'s factory and that its signature
+/// recovers to `expected_owner`.
+pub fn verify_safe_create_signature(
+    request: &TransactionRequest,
+    expected_owner: &Address,
+    config: &ContractConfig,
+    chain_id: u64,
+) -> Result<(), RelayerClientException> {
+    if request.transaction_type != TransactionType::SafeCreate.as_str() {
+        return Err(RelayerClientException::encoding(format!(
+            "expected a {} request, got {}",
+            TransactionType::SafeCreate.as_str(),
+            request.transaction_type
+        )));
+    }
+
+    let parse_address = |value: &str| {
+        Address::from_str(value)
+            .map_err(|e| RelayerClientException::encoding(format!("Invalid address: {}", e)))
+    };
+    if parse_address(&request.to)? != config.safe_factory {
+        return Err(RelayerClientException::encoding(format!(
+            "SAFE-CREATE must be sent to the factory {}",
+            config.safe_factory.to_alloy()
+        )));
+    }
+
+    let params = &request.signature_params;
+    let required = |field: &Option<String>, name: &str| {
+        field.clone().ok_or_else(|| {
+            RelayerClientException::encoding(format!("missing signatureParams.{}", name))
+        })
+    };
+    let struct_hash = create_safe_create_struct_hash(
+        config,
+        chain_id,
+        &parse_address(&required(&params.payment_token, "paymentToken")?)?,
+        &required(&params.payment, "payment")?,
+        &parse_address(&required(&params.payment_receiver, "paymentReceiver")?)?,
+    )?;
+
+    let hash = B256::from(hash_from_hex(&struct_hash)?);
+
+    let recovered = request
+        .signature
+        .parse::<Signature>()
+        .and_then(|sig| sig.recover_address_from_prehash(&hash))
+        .map_err(|e| RelayerClientException::InvalidSignature(e.to_string()))?;
+    if recovered != expected_owner.to_alloy() {
+        return Err(RelayerClientException::InvalidSignature(format!(
+            "signature recovers to {}, expected {}",
+            recovered,
+            expected_owner.to_alloy()
+        )));
+    }
+    Ok(())
+}
+
+#[test]
+fn test_create_safe_create_struct_hash() {
+    // Independent Python port of py-builder-relayer-client, not the client itself
+    let pk = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+    let signer: alloy::signers::local::PrivateKeySigner = pk.parse().unwrap();
+    let config = crate::config::get_contract_config(137).unwrap();
+    let usdc = Address::from_str("0x2791Bca1f2de4661ED88A30C99A7a9449Aa84174").unwrap();
+    let sponsor = Address::from_str("0x70997970C51812dc3A010C7d01b50e0d17dc79C8").unwrap();
+    let zero = Address::zero();
+
+    let cases = [
+        (
+            zero,
+            "0",
+            zero,
+            "0x563ac315294c5be01ab1f3b04a5abdfa39e8317a9d90679d4e63caf760b126a4",
+            "0xe3e791c24134b7bebe93b4771bd07c7fe7bbe115eeb0bf629ac3b7a435e7ac8d05f979729d873f7d0e16205becf48ee450aa382bc28c65eedcd6454e81d81f921b",
+        ),
+        (
+            usdc,
+            "250000",
+            sponsor,
+            "0xb373b339575c5d09585abf5568d7a370b76f16f39c6641e7dd207ca3ffb5f71f",
+            "0x5674606b0b083038a5de9d31fdcb90bbabaae864ebad8d8eb3b145ea53b0290c7410216ac8ebb08d645f0f1cf4e66a2673f9a68e567698538bad5b98d44aa6201b",
+        ),
+    ];
+    for (payment_token, payment, payment_receiver, expected_hash, expected_sig) in cases {
+        let struct_hash = create_safe_create_struct_hash(
+            &config,
+            137,
+            &payment_token,
+            payment,
+            &payment_receiver,
+        )
+        .unwrap();
+        assert_eq!(struct_hash, expected_hash);
+
+        let sig = create_safe_create_signature_sync(
+            &signer,
+            &config,
+            137,
+            &payment_token,
+            payment,
+            &payment_receiver,
+        )
+        .unwrap();
+        assert_eq!(sig, expected_sig);
+    }
+}
+
+#[test]
+fn test_build_safe_create_transaction_request_with_payment() {
+    use crate::conversion::ToEthers;
+
+    let signer: alloy::signers::local::PrivateKeySigner =
+        "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80"
+            .parse()
+            .unwrap();
+    let config = crate::config::get_contract_config(137).unwrap();
+    let usdc = Address::from_str("0x2791Bca1f2de4661ED88A30C99A7a9449Aa84174").unwrap();
+    let sponsor = Address::from_str("0x70997970C51812dc3A010C7d01b50e0d17dc79C8").unwrap();
+    let mut args = SafeCreateTransactionArgs {
+        from_address: signer.address().to_ethers(),
+        chain_id: 137,
+        payment_token: usdc,
+        payment: "250000".to_string(),
+        payment_receiver: sponsor,
+    };
+
+    let request = build_safe_create_transaction_request_sync(&signer, &args, &config).unwrap();
+    let params = &request.signature_params;
+    assert_eq!(
+        params.payment_token.as_deref(),
+        Some("0x2791Bca1f2de4661ED88A30C99A7a9449Aa84174")
+    );
+    assert_eq!(params.payment.as_deref(), Some("250000"));
+    assert_eq!(
+        params.payment_receiver.as_deref(),
+        Some("0x70997970C51812dc3A010C7d01b50e0d17dc79C8")
+    );
+
+    let digest = create_safe_create_struct_hash(&config, 137, &usdc, "250000", &sponsor).unwrap();
+    let recovered = request
+        .signature
+        .parse::<alloy_primitives::Signature>()
+        .unwrap()
+        .recover_address_from_prehash(&B256::from(hash_from_hex(&digest).unwrap()))
+        .unwrap();
+    assert_eq!(recovered, signer.address());
+    verify_safe_create_signature(&request, &args.from_address, &config, 137).unwrap();
+
+    // Signed for another factory name, i.e. another EIP-712 domain
+    let other = ContractConfig {
+        safe_factory_name: "Other Factory".to_string(),
+        ..config.clone()
+    };
+    assert!(matches!(
+        verify_safe_create_signature(&request, &args.from_address, &other, 137),
+        Err(RelayerClientException::InvalidSignature(_))
+    ));
+
+    args.payment = "1.5".to_string();
+    assert!(build_safe_create_transaction_request_sync(&signer, &args, &config).is_err());
+
+    args.payment = "1".to_string();
+    args.payment_token = Address::zero();
+    assert!(build_safe_create_transaction_request_sync(&signer, &args, &config).is_err());
+}
@@ -1,8 +1,18 @@
 pub mod create;
 pub mod derive;
+pub mod proxy;
 pub mod safe;
+pub mod signatures;
 
-pub use create::build_safe_create_transaction_request;
-pub use derive::derive;
-pub use safe::build_safe_transaction_request;
-
+pub use create::{
+    build_safe_create_transaction_request, build_safe_create_transaction_request_sync,
+    verify_safe_create_signature,
+};
+pub use proxy::{
+    build_proxy_transaction_request, build_proxy_transaction_request_sync, verify_proxy_signature,
+};
+pub use safe::{
+    build_safe_transaction_request, build_safe_transaction_request_sync,
+    build_unsigned_safe_transaction_request, verify_safe_request, verify_safe_signature,
+};
+pub use signatures::{SafeSignature, SafeSignatureCollector};
@@ -0,0 +1,294 @@
+use crate::config::ContractConfig;
+use crate::constants::DEFAULT_PROXY_GAS_LIMIT;
+use crate::conversion::ToAlloy;
+use crate::errors::RelayerClientException;
+use crate::models::{ProxyTransactionArgs, SignatureParams, TransactionRequest, TransactionType};
+use crate::utils::hash_from_hex;
+use alloy_primitives::Signature;
+use alloy_signer::{Signer, SignerSync};
+use ethers::core::utils::keccak256;
+use ethers::types::{Address, U256};
+use std::str::FromStr;
+
+fn u256_word(value: &str, name: &str) -> Result<[u8; 32], RelayerClientException> {
+    let value = U256::from_dec_str(value)
+        .map_err(|e| RelayerClientException::encoding(format!("Invalid {}: {}", name, e)))?;
+    let mut word = [0u8; 32];
+    value.to_big_endian(&mut word);
+    Ok(word)
+}
+
+/// Hash signed for a relay hub transaction:
+/// `keccak256("rlx:" ++ from ++ to ++ data ++ txFee ++ gasPrice ++ gasLimit ++ nonce ++ relayHub ++ relay)`.
+#[allow(clippy::too_many_arguments)]
+pub fn create_proxy_struct_hash(
+    from: &Address,
+    to: &Address,
+    data: &str,
+    tx_fee: &str,
+    gas_price: &str,
+    gas_limit: &str,
+    nonce: &str,
+    relay_hub: &Address,
+    relay: &Address,
+) -> Result<String, RelayerClientException> {
+    let data = data.strip_prefix("0x").unwrap_or(data);
+    let data_bytes = hex::decode(data)
+        .map_err(|e| RelayerClientException::encoding(format!("Invalid data: {}", e)))?;
+
+    let mut input = Vec::with_capacity(4 + 20 + 20 + data_bytes.len() + 4 * 32 + 20 + 20);
+    input.extend_from_slice(b"rlx:");
+    input.extend_from_slice(from.as_bytes());
+    input.extend_from_slice(to.as_bytes());
+    input.extend_from_slice(&data_bytes);
+    input.extend_from_slice(&u256_word(tx_fee, "tx_fee")?);
+    input.extend_from_slice(&u256_word(gas_price, "gas_price")?);
+    input.extend_from_slice(&u256_word(gas_limit, "gas_limit")?);
+    input.extend_from_slice(&u256_word(nonce, "nonce")?);
+    input.extend_from_slice(relay_hub.as_bytes());
+    input.extend_from_slice(relay.as_bytes());
+
+    Ok(format!("0x{}", hex::encode(keccak256(&input))))
+}
+
+pub async fn create_proxy_signature<S: Signer + Send + Sync + ?Sized>(
+    signer: &S,
+    struct_hash: &str,
+) -> Result<String, RelayerClientException> {
+    let hash = hash_from_hex(struct_hash)?;
+
+    signer
+        .sign_message(&hash)
+        .await
+        .map_err(RelayerClientException::Signing)
+        .map(|sig| sig.to_string())
+}
+
+pub fn create_proxy_signature_sync<S: SignerSync + ?Sized>(
+    signer: &S,
+    struct_hash: &str,
+) -> Result<String, RelayerClientException> {
+    let hash = hash_from_hex(struct_hash)?;
+
+    signer
+        .sign_message_sync(&hash)
+        .map_err(RelayerClientException::Signing)
+        .map(|sig| sig.to_string())
+}
+
+/// Builds the PROXY transaction request and the hash the signer has to sign.
+/// The returned request has an empty `signature`.
+fn prepare_proxy_transaction_request(
+    args: &ProxyTransactionArgs,
+    config: &ContractConfig,
+    metadata: Option<&str>,
+) -> Result<(String, TransactionRequest), RelayerClientException> {
+    let (Some(proxy_factory), Some(relay_hub)) = (config.proxy_factory, config.relay_hub) else {
+        return Err(RelayerClientException::InvalidConfig(
+            "proxy_factory and relay_hub are required for proxy wallets".to_string(),
+        ));
+    };
+    let proxy_wallet = config.proxy_wallet_address(&args.from_address)?;
+    let relayer_fee = "0";
+    let gas_limit = args.gas_limit.as_deref().unwrap_or(DEFAULT_PROXY_GAS_LIMIT);
+
+    let struct_hash = create_proxy_struct_hash(
+        &args.from_address,
+        &proxy_factory,
+        &args.data,
+        relayer_fee,
+        &args.gas_price,
+        gas_limit,
+        &args.nonce,
+        &relay_hub,
+        &args.relay,
+    )?;
+
+    let sig_params = SignatureParams {
+        gas_price: Some(args.gas_price.clone()),
+        operation: None,
+        safe_txn_gas: None,
+        base_gas: None,
+        gas_token: None,
+        refund_receiver: None,
+        payment_token: None,
+        payment: None,
+        payment_receiver: None,
+        gas_limit: Some(gas_limit.to_string()),
+        relayer_fee: Some(relayer_fee.to_string()),
+        relay_hub: Some(format!("{}", relay_hub.to_alloy())), // Display trait provides checksummed format
+        relay: Some(format!("{}", args.relay.to_alloy())),
+    };
+
+    let request = TransactionRequest {
+        transaction_type: TransactionType::Proxy.as_str().to_string(),
+        from_address: format!("{}", args.from_address.to_alloy()),
+        to: format!("{}", proxy_factory.to_alloy()),
+        proxy: format!("{}", proxy_wallet.to_alloy()),
+        value: None,
+        data: args.data.clone(),
+        nonce: Some(args.nonce.clone()),
+        signature: String::new(),
+        signature_params: sig_params,
+        metadata: metadata.map(|s| s.to_string()),
+    };
+
+    Ok((struct_hash, request))
+}
+
+pub async fn build_proxy_transaction_request<S: Signer + Send + Sync + ?Sized>(
+    signer: &S,
+    args: &ProxyTransactionArgs,
+    config: &ContractConfig,
+    metadata: Option<&str>,
+) -> Result<TransactionRequest, RelayerClientException> {
+    let (struct_hash, mut request) = prepare_proxy_transaction_request(args, config, metadata)?;
+    request.signature = create_proxy_signature(signer, &struct_hash).await?;
+    Ok(request)
+}
+
+pub fn build_proxy_transaction_request_sync<S: SignerSync + ?Sized>(
+    signer: &S,
+    args: &ProxyTransactionArgs,
+    config: &ContractConfig,
+    metadata: Option<&str>,
+) -> Result<TransactionRequest, RelayerClientException> {
+    let (struct_hash, mut request) = prepare_proxy_transaction_request(args, config, metadata)?;
+    request.signature = create_proxy_signature_sync(signer, &struct_hash)?;
+    Ok(request)
+}
+
+/// Rebuilds the relay hub hash from a signed PROXY request and checks that it
+/// is sent through `config`'s factory and relay hub for `expected_owner`'s
+/// proxy wallet, and that its signature recovers to `expected_owner`.
+pub fn verify_proxy_signature(
+    request: &TransactionRequest,
+    expected_owner: &Address,
+    config: &ContractConfig,
+) -> Result<(), RelayerClientException> {
+    if request.transaction_type != TransactionType::Proxy.as_str() {
+        return Err(RelayerClientException::encoding(format!(
+            "expected a {} request, got {}",
+            TransactionType::Proxy.as_str(),
+            request.transaction_type
+        )));
+    }
+    let (Some(proxy_factory), Some(relay_hub)) = (config.proxy_factory, config.relay_hub) else {
+        return Err(RelayerClientException::InvalidConfig(
+            "proxy_factory and relay_hub are required for proxy wallets".to_string(),
+        ));
+    };
+
+    let params = &request.signature_params;
+    let required = |field: &Option<String>, name: &str| {
+        field.clone().ok_or_else(|| {
+            RelayerClientException::encoding(format!("missing signatureParams.{}", name))
+        })
+    };
+    let parse_address = |value: &str| {
+        Address::from_str(value)
+            .map_err(|e| RelayerClientException::encoding(format!("Invalid address: {}", e)))
+    };
+    if parse_address(&request.to)? != proxy_factory
+        || parse_address(&required(&params.relay_hub, "relayHub")?)? != relay_hub
+    {
+        return Err(RelayerClientException::encoding(format!(
+            "PROXY must be sent to the factory {} through the relay hub {}",
+            proxy_factory.to_alloy(),
+            relay_hub.to_alloy()
+        )));
+    }
+    let proxy_wallet = config.proxy_wallet_address(expected_owner)?;
+    if parse_address(&request.proxy)? != proxy_wallet {
+        return Err(RelayerClientException::InvalidSignature(format!(
+            "request proxy {} is not the proxy wallet {} of {}",
+            request.proxy,
+            proxy_wallet.to_alloy(),
+            expected_owner.to_alloy()
+        )));
+    }
+
+    let struct_hash = create_proxy_struct_hash(
+        expected_owner,
+        &proxy_factory,
+        &request.data,
+        &required(&params.relayer_fee, "relayerFee")?,
+        &required(&params.gas_price, "gasPrice")?,
+        &required(&params.gas_limit, "gasLimit")?,
+        &required(&request.nonce, "nonce")?,
+        &relay_hub,
+        &parse_address(&required(&params.relay, "relay")?)?,
+    )?;
+    let hash = hash_from_hex(&struct_hash)?;
+
+    let recovered = request
+        .signature
+        .parse::<Signature>()
+        .and_then(|sig| sig.recover_address_from_msg(hash))
+        .map_err(|e| RelayerClientException::InvalidSignature(e.to_string()))?;
+    if recovered != expected_owner.to_alloy() {
+        return Err(RelayerClientException::InvalidSignature(format!(
+            "signature recovers to {}, expected {}",
+            recovered,
+            expected_owner.to_alloy()
+        )));
+    }
+    Ok(())
+}
+
+#[test]
+fn test_proxy_request_parity() {
+    use crate::conversion::ToEthers;
+
+    // Independent Python port of py-builder-relayer-client, not the client itself
+    let signer: alloy::signers::local::PrivateKeySigner =
+        "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80"
+            .parse()
+            .unwrap();
+    let config = crate::config::get_contract_config(137).unwrap();
+    let args = ProxyTransactionArgs {
+        from_address: signer.address().to_ethers(),
+        nonce: "7".to_string(),
+        gas_price: "0".to_string(),
+        gas_limit: None,
+        data: "0x34ee9791\
+               0000000000000000000000000000000000000000000000000000000000000020\
+               0000000000000000000000000000000000000000000000000000000000000000"
+            .to_string(),
+        relay: "0x70997970C51812dc3A010C7d01b50e0d17dc79C8"
+            .parse()
+            .unwrap(),
+    };
+
+    let (struct_hash, _) = prepare_proxy_transaction_request(&args, &config, None).unwrap();
+    assert_eq!(
+        struct_hash,
+        "0xa3639895a83afd217f2ff00d7ee5078889fa3c922f73f35053592d97fecd8702"
+    );
+
+    let request = build_proxy_transaction_request_sync(&signer, &args, &config, None).unwrap();
+    assert_eq!(
+        request.signature,
+        "0x1ee2f1f54f09ae21b239df8da6863539847c9d2c95c20afd068e5af8132ac6a87664f615b0484bd3195deff9074431c6d84d5056519bfc26623eb1bbcc88bff21b"
+    );
+    assert_eq!(
+        request.proxy.to_lowercase(),
+        "0x365f0ca36ae1f641e02fe3b7743673da42a13a70"
+    );
+    assert_eq!(request.to, "0xaB45c5A4B0c941a2F231C04C3f49182e1A254052");
+    assert_eq!(
+        request.signature_params.gas_limit.as_deref(),
+        Some("10000000")
+    );
+    assert_eq!(request.signature_params.relayer_fee.as_deref(), Some("0"));
+
+    let owner = signer.address().to_ethers();
+    verify_proxy_signature(&request, &owner, &config).unwrap();
+    assert!(verify_proxy_signature(&request, &Address::zero(), &config).is_err());
+    let mut tampered = request.clone();
+    tampered.nonce = Some("8".to_string());
+    assert!(matches!(
+        verify_proxy_signature(&tampered, &owner, &config),
+        Err(RelayerClientException::InvalidSignature(_))
+    ));
+}
@@ -1,44 +1,35 @@
-use crate::builder::derive::derive;
 use crate::config::ContractConfig;
-use crate::constants::ZERO_ADDRESS;
 use crate::conversion::ToAlloy;
 use crate::encode::safe::create_safe_multisend_transaction;
+use crate::errors::RelayerClientException;
 use crate::model::safe_tx::SafeTx;
 use crate::models::{
     OperationType, SafeTransaction, SafeTransactionArgs, SignatureParams, SplitSig,
     TransactionRequest, TransactionType,
 };
+use crate::utils::{decode_hex, hash_from_hex};
 use alloy::dyn_abi::Eip712Domain;
-use alloy::signers::local::PrivateKeySigner;
 use alloy::sol_types::SolStruct;
-use alloy_signer::SignerSync;
+use alloy_primitives::{B256, Signature};
+use alloy_signer::{Signer, SignerSync};
 use ethers::types::{Address, U256};
 use std::str::FromStr;
 
 pub fn aggregate_transaction(
     txns: &[SafeTransaction],
     safe_multisend: &Address,
-) -> SafeTransaction {
-    if txns.len() == 1 {
-        return txns[0].clone();
-    } else {
-        create_safe_multisend_transaction(txns, safe_multisend)
-    }
+) -> Result<SafeTransaction, RelayerClientException> {
+    create_safe_multisend_transaction(txns, safe_multisend)
 }
 
-pub fn split_signature(sig_hex: &str) -> Result<SplitSig, String> {
-    let sig = if sig_hex.starts_with("0x") {
-        hex::decode(&sig_hex[2..])
-    } else {
-        hex::decode(sig_hex)
-    }
-    .map_err(|e| format!("Invalid hex: {}", e))?;
+pub fn split_signature(sig_hex: &str) -> Result<SplitSig, RelayerClientException> {
+    let sig = decode_hex(sig_hex, "signature")?;
 
     if sig.len() != 65 {
-        return Err(format!(
+        return Err(RelayerClientException::InvalidSignature(format!(
             "Invalid signature length: expected 65 bytes, got {}",
             sig.len()
-        ));
+        )));
     }
 
     let r = U256::from_big_endian(&sig[0..32]);
@@ -50,16 +41,16 @@ pub fn split_signature(sig_hex: &str) -> Result<SplitSig, String> {
     } else if v_raw == 27 || v_raw == 28 {
         v_raw + 4
     } else {
-        return Err(format!(
+        return Err(RelayerClientException::InvalidSignature(format!(
             "Invalid signature 'v' (expected 0,1,27,28), got {}",
             v_raw
-        ));
+        )));
     };
 
     Ok(SplitSig { r, s, v })
 }
 
-pub fn split_and_pack_sig(sig_hex: &str) -> Result<String, String> {
+pub fn split_and_pack_sig(sig_hex: &str) -> Result<String, RelayerClientException> {
     let split_sig = split_signature(sig_hex)?;
     let mut packed = Vec::new();
     let mut r_bytes = [0u8; 32];
@@ -72,28 +63,28 @@ pub fn split_and_pack_sig(sig_hex: &str) -> Result<String, String> {
     Ok(format!("0x{}", hex::encode(&packed)))
 }
 
-pub fn create_safe_signature(
-    signer: &PrivateKeySigner,
+pub async fn create_safe_signature<S: Signer + Send + Sync + ?Sized>(
+    signer: &S,
     struct_hash: &str,
-) -> Result<String, String> {
-    let hash = if struct_hash.starts_with("0x") {
-        hex::decode(&struct_hash[2..])
-    } else {
-        hex::decode(struct_hash)
-    }
-    .map_err(|e| format!("Invalid hash: {}", e))?;
+) -> Result<String, RelayerClientException> {
+    let hash_array = hash_from_hex(struct_hash)?;
 
-    if hash.len() != 32 {
-        return Err("Invalid hash length".to_string());
-    }
+    signer
+        .sign_message(&hash_array)
+        .await
+        .map_err(RelayerClientException::Signing)
+        .map(|sig| sig.to_string())
+}
 
-    let hash_array: [u8; 32] = hash
-        .try_into()
-        .map_err(|_| "Failed to convert hash to [u8; 32]")?;
+pub fn create_safe_signature_sync<S: SignerSync + ?Sized>(
+    signer: &S,
+    struct_hash: &str,
+) -> Result<String, RelayerClientException> {
+    let hash_array = hash_from_hex(struct_hash)?;
 
     signer
         .sign_message_sync(&hash_array)
-        .map_err(|e| format!("Signing failed: {}", e))
+        .map_err(RelayerClientException::Signing)
         .map(|sig| sig.to_string())
 }
 pub fn create_struct_hash(
@@ -109,21 +100,19 @@ pub fn create_struct_hash(
     gas_token: &Address,
     refund_receiver: &Address,
     nonce: &str,
-) -> Result<String, String> {
-    let value_u256 = U256::from_dec_str(value).map_err(|e| format!("Invalid value: {}", e))?;
-    let safe_tx_gas_u256 =
-        U256::from_dec_str(safe_tx_gas).map_err(|e| format!("Invalid safe_tx_gas: {}", e))?;
-    let base_gas_u256 =
-        U256::from_dec_str(base_gas).map_err(|e| format!("Invalid base_gas: {}", e))?;
-    let gas_price_u256 =
-        U256::from_dec_str(gas_price).map_err(|e| format!("Invalid gas_price: {}", e))?;
-    let nonce_u256 = U256::from_dec_str(nonce).map_err(|e| format!("Invalid nonce: {}", e))?;
-
-    let data_bytes = if data.starts_with("0x") {
-        hex::decode(&data[2..]).unwrap_or_default()
-    } else {
-        hex::decode(data).unwrap_or_default()
-    };
+) -> Result<String, RelayerClientException> {
+    let value_u256 = U256::from_dec_str(value)
+        .map_err(|e| RelayerClientException::encoding(format!("Invalid value: {}", e)))?;
+    let safe_tx_gas_u256 = U256::from_dec_str(safe_tx_gas)
+        .map_err(|e| RelayerClientException::encoding(format!("Invalid safe_tx_gas: {}", e)))?;
+    let base_gas_u256 = U256::from_dec_str(base_gas)
+        .map_err(|e| RelayerClientException::encoding(format!("Invalid base_gas: {}", e)))?;
+    let gas_price_u256 = U256::from_dec_str(gas_price)
+        .map_err(|e| RelayerClientException::encoding(format!("Invalid gas_price: {}", e)))?;
+    let nonce_u256 = U256::from_dec_str(nonce)
+        .map_err(|e| RelayerClientException::encoding(format!("Invalid nonce: {}", e)))?;
+
+    let data_bytes = decode_hex(data, "data")?;
 
     let safe_tx = SafeTx {
         to: to.to_alloy(),
@@ -148,21 +137,21 @@ pub fn create_struct_hash(
 
     Ok(safe_tx.eip712_signing_hash(&domain).to_string())
 }
-pub fn build_safe_transaction_request(
-    signer: &PrivateKeySigner,
+/// Builds the SAFE transaction request and the `SafeTx` hash its owners have
+/// to sign. The returned request has an empty `signature`; for multi-owner
+/// Safes, fill it with [`SafeSignatureCollector::pack`].
+///
+/// [`SafeSignatureCollector::pack`]: crate::builder::signatures::SafeSignatureCollector::pack
+pub fn build_unsigned_safe_transaction_request(
     args: &SafeTransactionArgs,
     config: &ContractConfig,
     metadata: Option<&str>,
-) -> Result<TransactionRequest, String> {
-    let factory = config.safe_factory;
+) -> Result<(String, TransactionRequest), RelayerClientException> {
     let multisend = config.safe_multisend;
-    let transaction = aggregate_transaction(&args.transactions, &multisend);
-    let safe_txn_gas = "0";
-    let base_gas = "0";
-    let gas_price = "0";
-    let gas_token = Address::from_str(ZERO_ADDRESS).unwrap();
-    let refund_receiver = Address::from_str(ZERO_ADDRESS).unwrap();
-    let safe_address = derive(&args.from_address, &factory);
+    let transaction = aggregate_transaction(&args.transactions, &multisend)?;
+    let gas = args.gas_params.clone().unwrap_or_default();
+    gas.validate()?;
+    let safe_address = config.safe_address(&args.from_address)?;
 
     let struct_hash = create_struct_hash(
         args.chain_id,
@@ -171,30 +160,31 @@ pub fn build_safe_transaction_request(
         &transaction.value,
         &transaction.data,
         transaction.operation,
-        safe_txn_gas,
-        base_gas,
-        gas_price,
-        &gas_token,
-        &refund_receiver,
+        &gas.safe_tx_gas,
+        &gas.base_gas,
+        &gas.gas_price,
+        &gas.gas_token,
+        &gas.refund_receiver,
         &args.nonce,
     )?;
 
-    let sig = create_safe_signature(signer, &struct_hash)?;
-    let packed_sig = split_and_pack_sig(&sig)?;
-
     let sig_params = SignatureParams {
-        gas_price: Some(gas_price.to_string()),
+        gas_price: Some(gas.gas_price.clone()),
         operation: Some(transaction.operation.as_u8().to_string()),
-        safe_txn_gas: Some(safe_txn_gas.to_string()),
-        base_gas: Some(base_gas.to_string()),
-        gas_token: Some(format!("{}", gas_token.to_alloy())), // Display trait provides checksummed format
-        refund_receiver: Some(format!("{}", refund_receiver.to_alloy())), // Display trait provides checksummed format
+        safe_txn_gas: Some(gas.safe_tx_gas.clone()),
+        base_gas: Some(gas.base_gas.clone()),
+        gas_token: Some(format!("{}", gas.gas_token.to_alloy())), // Display trait provides checksummed format
+        refund_receiver: Some(format!("{}", gas.refund_receiver.to_alloy())), // Display trait provides checksummed format
         payment_token: None,
         payment: None,
         payment_receiver: None,
+        gas_limit: None,
+        relayer_fee: None,
+        relay_hub: None,
+        relay: None,
     };
 
-    Ok(TransactionRequest {
+    let request = TransactionRequest {
         transaction_type: TransactionType::Safe.as_str().to_string(),
         from_address: format!("{}", args.from_address.to_alloy()), // Display trait provides checksummed format
         to: format!("{}", transaction.to.to_alloy()), // Display trait provides checksummed format
@@ -202,10 +192,152 @@ pub fn build_safe_transaction_request(
         value: Some(transaction.value),
         data: transaction.data,
         nonce: Some(args.nonce.clone()),
-        signature: packed_sig,
+        signature: String::new(),
         signature_params: sig_params,
         metadata: metadata.map(|s| s.to_string()),
-    })
+    };
+
+    Ok((struct_hash, request))
+}
+
+pub async fn build_safe_transaction_request<S: Signer + Send + Sync + ?Sized>(
+    signer: &S,
+    args: &SafeTransactionArgs,
+    config: &ContractConfig,
+    metadata: Option<&str>,
+) -> Result<TransactionRequest, RelayerClientException> {
+    let (struct_hash, mut request) =
+        build_unsigned_safe_transaction_request(args, config, metadata)?;
+    let sig = create_safe_signature(signer, &struct_hash).await?;
+    request.signature = split_and_pack_sig(&sig)?;
+    Ok(request)
+}
+
+pub fn build_safe_transaction_request_sync<S: SignerSync + ?Sized>(
+    signer: &S,
+    args: &SafeTransactionArgs,
+    config: &ContractConfig,
+    metadata: Option<&str>,
+) -> Result<TransactionRequest, RelayerClientException> {
+    let (struct_hash, mut request) =
+        build_unsigned_safe_transaction_request(args, config, metadata)?;
+    let sig = create_safe_signature_sync(signer, &struct_hash)?;
+    request.signature = split_and_pack_sig(&sig)?;
+    Ok(request)
+}
+
+/// Rebuilds the `SafeTx` hash from a signed SAFE request and checks that its
+/// signature recovers to `expected_owner`.
+///
+/// Both the `eth_sign` form produced by [`split_and_pack_sig`] (v of 31/32)
+/// and a plain EIP-712 signature (v of 27/28) are accepted.
+pub fn verify_safe_signature(
+    request: &TransactionRequest,
+    expected_owner: &Address,
+    chain_id: u64,
+) -> Result<(), RelayerClientException> {
+    if request.transaction_type != TransactionType::Safe.as_str() {
+        return Err(RelayerClientException::encoding(format!(
+            "expected a {} request, got {}",
+            TransactionType::Safe.as_str(),
+            request.transaction_type
+        )));
+    }
+
+    let params = &request.signature_params;
+    let required = |field: &Option<String>, name: &str| {
+        field.clone().ok_or_else(|| {
+            RelayerClientException::encoding(format!("missing signatureParams.{}", name))
+        })
+    };
+    let parse_address = |value: &str| {
+        Address::from_str(value)
+            .map_err(|e| RelayerClientException::encoding(format!("Invalid address: {}", e)))
+    };
+
+    let operation = required(&params.operation, "operation")?;
+    let operation = operation
+        .parse::<u8>()
+        .ok()
+        .and_then(OperationType::from_u8)
+        .ok_or_else(|| {
+            RelayerClientException::encoding(format!("Invalid operation: {}", operation))
+        })?;
+
+    let struct_hash = create_struct_hash(
+        chain_id,
+        &parse_address(&request.proxy)?,
+        &parse_address(&request.to)?,
+        request.value.as_deref().unwrap_or("0"),
+        &request.data,
+        operation,
+        &required(&params.safe_txn_gas, "safeTxnGas")?,
+        &required(&params.base_gas, "baseGas")?,
+        &required(&params.gas_price, "gasPrice")?,
+        &parse_address(&required(&params.gas_token, "gasToken")?)?,
+        &parse_address(&required(&params.refund_receiver, "refundReceiver")?)?,
+        &required(&request.nonce, "nonce")?,
+    )?;
+    let hash = B256::from(hash_from_hex(&struct_hash)?);
+
+    let sig_hex = request.signature.trim_start_matches("0x");
+    let mut sig = hex::decode(sig_hex)
+        .map_err(|e| RelayerClientException::encoding(format!("Invalid hex: {}", e)))?;
+    if sig.len() != 65 {
+        return Err(RelayerClientException::InvalidSignature(format!(
+            "Invalid signature length: expected 65 bytes, got {}",
+            sig.len()
+        )));
+    }
+
+    let recovered = match sig[64] {
+        31 | 32 => {
+            // Undo the eth_sign adjustment made by `split_signature`
+            sig[64] -= 4;
+            Signature::from_raw(&sig)
+                .and_then(|sig| sig.recover_address_from_msg(hash))
+                .map_err(|e| RelayerClientException::InvalidSignature(e.to_string()))?
+        }
+        27 | 28 => Signature::from_raw(&sig)
+            .and_then(|sig| sig.recover_address_from_prehash(&hash))
+            .map_err(|e| RelayerClientException::InvalidSignature(e.to_string()))?,
+        v => {
+            return Err(RelayerClientException::InvalidSignature(format!(
+                "Invalid signature 'v' (expected 27,28,31,32), got {}",
+                v
+            )));
+        }
+    };
+
+    if recovered != expected_owner.to_alloy() {
+        return Err(RelayerClientException::InvalidSignature(format!(
+            "signature recovers to {}, expected {}",
+            recovered,
+            expected_owner.to_alloy()
+        )));
+    }
+    Ok(())
+}
+
+/// Checks that a signed SAFE request targets `expected_owner`'s Safe under
+/// `config` and that its signature recovers to `expected_owner`, see
+/// [`verify_safe_signature`].
+pub fn verify_safe_request(
+    request: &TransactionRequest,
+    expected_owner: &Address,
+    config: &ContractConfig,
+    chain_id: u64,
+) -> Result<(), RelayerClientException> {
+    let expected_safe = config.safe_address(expected_owner)?;
+    let proxy = Address::from_str(&request.proxy)
+        .map_err(|e| RelayerClientException::encoding(format!("Invalid address: {}", e)))?;
+    if proxy != expected_safe {
+        return Err(RelayerClientException::InvalidSignature(format!(
+            "request proxy {:?} is not the Safe {:?} of {:?}",
+            proxy, expected_safe, expected_owner
+        )));
+    }
+    verify_safe_signature(request, expected_owner, chain_id)
 }
 
 #[test]
@@ -229,3 +361,188 @@ fn test_create_struct_hash() {
         "0xb067b60440424df66ce7491afa39f5ba1977ac0a8466cbb017280cc01304698f"
     );
 }
+
+#[tokio::test]
+async fn test_build_safe_transaction_request_with_async_signer() {
+    use crate::conversion::ToEthers;
+    use alloy::signers::local::PrivateKeySigner;
+    use alloy_primitives::ChainId;
+
+    // Stand-in for a remote or KMS signer: only the async trait is implemented
+    struct RemoteSigner(PrivateKeySigner);
+
+    #[async_trait::async_trait]
+    impl Signer for RemoteSigner {
+        async fn sign_hash(&self, hash: &B256) -> alloy_signer::Result<Signature> {
+            self.0.sign_hash_sync(hash)
+        }
+
+        fn address(&self) -> alloy_primitives::Address {
+            self.0.address()
+        }
+
+        fn chain_id(&self) -> Option<ChainId> {
+            None
+        }
+
+        fn set_chain_id(&mut self, _chain_id: Option<ChainId>) {}
+    }
+
+    let local: PrivateKeySigner =
+        "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80"
+            .parse()
+            .unwrap();
+    let config = crate::config::get_contract_config(137).unwrap();
+    let args = SafeTransactionArgs {
+        from_address: local.address().to_ethers(),
+        nonce: "3".to_string(),
+        chain_id: 137,
+        transactions: vec![SafeTransaction {
+            to: Address::from_str("0x2791Bca1f2de4661ED88A30C99A7a9449Aa84174").unwrap(),
+            operation: OperationType::Call,
+            data: "0x".to_string(),
+            value: "0".to_string(),
+        }],
+        gas_params: None,
+    };
+
+    let sync_request = build_safe_transaction_request_sync(&local, &args, &config, None).unwrap();
+    let async_request = build_safe_transaction_request(&RemoteSigner(local), &args, &config, None)
+        .await
+        .unwrap();
+
+    assert_eq!(async_request.signature, sync_request.signature);
+    assert_eq!(async_request.signature.len(), 2 + 65 * 2);
+}
+
+#[test]
+fn test_verify_safe_signature() {
+    use crate::conversion::ToEthers;
+    use alloy::signers::local::PrivateKeySigner;
+
+    let signer: PrivateKeySigner =
+        "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80"
+            .parse()
+            .unwrap();
+    let owner = signer.address().to_ethers();
+    let config = crate::config::get_contract_config(137).unwrap();
+    let args = SafeTransactionArgs {
+        from_address: owner,
+        nonce: "12".to_string(),
+        chain_id: 137,
+        transactions: vec![SafeTransaction {
+            to: Address::from_str("0x2791Bca1f2de4661ED88A30C99A7a9449Aa84174").unwrap(),
+            operation: OperationType::Call,
+            data: "0x095ea7b3".to_string(),
+            value: "0".to_string(),
+        }],
+        gas_params: None,
+    };
+    let request = build_safe_transaction_request_sync(&signer, &args, &config, None).unwrap();
+
+    verify_safe_signature(&request, &owner, 137).unwrap();
+    assert!(verify_safe_signature(&request, &Address::zero(), 137).is_err());
+    // Signed for Polygon, so the Amoy domain recovers a different address
+    assert!(verify_safe_signature(&request, &owner, 80002).is_err());
+
+    let mut tampered = request.clone();
+    tampered.nonce = Some("13".to_string());
+    assert!(verify_safe_signature(&tampered, &owner, 137).is_err());
+
+    verify_safe_request(&request, &owner, &config, 137).unwrap();
+}
+
+#[test]
+fn test_verify_safe_request_rejects_a_wrong_proxy() {
+    use crate::conversion::ToEthers;
+    use alloy::signers::local::PrivateKeySigner;
+
+    let signer: PrivateKeySigner =
+        "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80"
+            .parse()
+            .unwrap();
+    let owner = signer.address().to_ethers();
+    let config = crate::config::get_contract_config(137).unwrap();
+    // A config whose factory derives another Safe for the same owner, so the
+    // request is validly signed, just for the wrong wallet
+    let mut other = config.clone();
+    other.safe_factory = Address::from_str("0x0000000000000000000000000000000000000001").unwrap();
+    let args = SafeTransactionArgs {
+        from_address: owner,
+        nonce: "12".to_string(),
+        chain_id: 137,
+        transactions: vec![SafeTransaction {
+            to: Address::from_str("0x2791Bca1f2de4661ED88A30C99A7a9449Aa84174").unwrap(),
+            operation: OperationType::Call,
+            data: "0x095ea7b3".to_string(),
+            value: "0".to_string(),
+        }],
+        gas_params: None,
+    };
+    let request = build_safe_transaction_request_sync(&signer, &args, &other, None).unwrap();
+
+    verify_safe_signature(&request, &owner, 137).unwrap();
+    assert!(matches!(
+        verify_safe_request(&request, &owner, &config, 137),
+        Err(RelayerClientException::InvalidSignature(_))
+    ));
+}
+
+#[test]
+fn test_build_safe_transaction_request_with_gas_refund() {
+    use crate::conversion::ToEthers;
+    use crate::models::SafeGasParams;
+    use alloy::signers::local::PrivateKeySigner;
+
+    let signer: PrivateKeySigner =
+        "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80"
+            .parse()
+            .unwrap();
+    let owner = signer.address().to_ethers();
+    let config = crate::config::get_contract_config(137).unwrap();
+    let usdc = Address::from_str("0x2791Bca1f2de4661ED88A30C99A7a9449Aa84174").unwrap();
+    let gas = SafeGasParams {
+        safe_tx_gas: "100000".to_string(),
+        base_gas: "21000".to_string(),
+        gas_price: "1".to_string(),
+        gas_token: usdc,
+        refund_receiver: Address::zero(),
+    };
+    let mut args = SafeTransactionArgs {
+        from_address: owner,
+        nonce: "4".to_string(),
+        chain_id: 137,
+        transactions: vec![SafeTransaction {
+            to: usdc,
+            operation: OperationType::Call,
+            data: "0x".to_string(),
+            value: "0".to_string(),
+        }],
+        gas_params: Some(gas.clone()),
+    };
+    let request = build_safe_transaction_request_sync(&signer, &args, &config, None).unwrap();
+
+    let params = &request.signature_params;
+    assert_eq!(params.safe_txn_gas.as_deref(), Some("100000"));
+    assert_eq!(params.base_gas.as_deref(), Some("21000"));
+    assert_eq!(params.gas_price.as_deref(), Some("1"));
+    assert_eq!(
+        params.gas_token.as_deref(),
+        Some("0x2791Bca1f2de4661ED88A30C99A7a9449Aa84174")
+    );
+    verify_safe_signature(&request, &owner, 137).unwrap();
+
+    // A refund with no gas limit on the inner call
+    args.gas_params = Some(SafeGasParams {
+        safe_tx_gas: "0".to_string(),
+        ..gas.clone()
+    });
+    assert!(build_safe_transaction_request_sync(&signer, &args, &config, None).is_err());
+
+    // A gas token without a gas price
+    args.gas_params = Some(SafeGasParams {
+        gas_price: "0".to_string(),
+        ..gas
+    });
+    assert!(build_safe_transaction_request_sync(&signer, &args, &config, None).is_err());
+}
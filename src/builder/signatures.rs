@@ -0,0 +1,277 @@
+use crate::conversion::ToAlloy;
+use crate::errors::RelayerClientException;
+use crate::utils::hash_from_hex;
+use alloy_primitives::{B256, Signature};
+use ethers::types::{Address, U256};
+use std::collections::BTreeMap;
+
+/// One owner's signature over a `SafeTx` hash, in any of the forms the Safe
+/// contract's `checkSignatures` accepts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SafeSignature {
+    /// `eth_sign` over the hash, packed with `v + 4`.
+    EthSign(Signature),
+    /// Raw signature over the EIP-712 hash, packed with `v` as 27/28.
+    Eip712(Signature),
+    /// The owner approved the hash on-chain with `approveHash`, packed with `v = 1`.
+    ApprovedHash,
+    /// EIP-1271 signature checked by the owner contract, packed with `v = 0`
+    /// and the bytes appended after the static part.
+    Contract(Vec<u8>),
+}
+
+impl SafeSignature {
+    fn static_part(&self, owner: &Address, dynamic_offset: usize) -> [u8; 65] {
+        let mut packed = [0u8; 65];
+        match self {
+            SafeSignature::EthSign(sig) | SafeSignature::Eip712(sig) => {
+                packed[..32].copy_from_slice(&sig.r().to_be_bytes::<32>());
+                packed[32..64].copy_from_slice(&sig.s().to_be_bytes::<32>());
+                let v = 27 + sig.v() as u8;
+                packed[64] = match self {
+                    SafeSignature::EthSign(_) => v + 4,
+                    _ => v,
+                };
+            }
+            SafeSignature::ApprovedHash => {
+                packed[12..32].copy_from_slice(owner.as_bytes());
+                packed[64] = 1;
+            }
+            SafeSignature::Contract(_) => {
+                packed[12..32].copy_from_slice(owner.as_bytes());
+                U256::from(dynamic_offset).to_big_endian(&mut packed[32..64]);
+                packed[64] = 0;
+            }
+        }
+        packed
+    }
+}
+
+/// Collects owner signatures for a single `SafeTx` hash and packs them the
+/// way the Safe contract expects: sorted by owner address, static parts
+/// first, EIP-1271 payloads last.
+#[derive(Debug, Clone)]
+pub struct SafeSignatureCollector {
+    safe_tx_hash: B256,
+    signatures: BTreeMap<Address, SafeSignature>,
+}
+
+impl SafeSignatureCollector {
+    pub fn new(safe_tx_hash: &str) -> Result<Self, RelayerClientException> {
+        Ok(SafeSignatureCollector {
+            safe_tx_hash: B256::from(hash_from_hex(safe_tx_hash)?),
+            signatures: BTreeMap::new(),
+        })
+    }
+
+    /// Adds an `eth_sign` signature, as returned by `create_safe_signature`.
+    /// The signer is recovered and must match `owner`.
+    pub fn add_eth_sign(
+        &mut self,
+        owner: Address,
+        sig_hex: &str,
+    ) -> Result<(), RelayerClientException> {
+        let sig = parse_signature(sig_hex)?;
+        let recovered = sig
+            .recover_address_from_msg(self.safe_tx_hash)
+            .map_err(|e| RelayerClientException::InvalidSignature(e.to_string()))?;
+        self.check_owner(&owner, &recovered)?;
+        self.add(owner, SafeSignature::EthSign(sig));
+        Ok(())
+    }
+
+    /// Adds a signature made directly over the EIP-712 `SafeTx` hash.
+    /// The signer is recovered and must match `owner`.
+    pub fn add_eip712(
+        &mut self,
+        owner: Address,
+        sig_hex: &str,
+    ) -> Result<(), RelayerClientException> {
+        let sig = parse_signature(sig_hex)?;
+        let recovered = sig
+            .recover_address_from_prehash(&self.safe_tx_hash)
+            .map_err(|e| RelayerClientException::InvalidSignature(e.to_string()))?;
+        self.check_owner(&owner, &recovered)?;
+        self.add(owner, SafeSignature::Eip712(sig));
+        Ok(())
+    }
+
+    pub fn add_approved_hash(&mut self, owner: Address) {
+        self.add(owner, SafeSignature::ApprovedHash);
+    }
+
+    pub fn add_contract_signature(
+        &mut self,
+        owner: Address,
+        signature: &str,
+    ) -> Result<(), RelayerClientException> {
+        let signature = signature.strip_prefix("0x").unwrap_or(signature);
+        let bytes = hex::decode(signature)
+            .map_err(|e| RelayerClientException::encoding(format!("Invalid hex: {}", e)))?;
+        self.add(owner, SafeSignature::Contract(bytes));
+        Ok(())
+    }
+
+    /// Adds a signature without verification, replacing any previous one
+    /// from the same owner.
+    pub fn add(&mut self, owner: Address, signature: SafeSignature) {
+        self.signatures.insert(owner, signature);
+    }
+
+    pub fn len(&self) -> usize {
+        self.signatures.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.signatures.is_empty()
+    }
+
+    pub fn has_threshold(&self, threshold: usize) -> bool {
+        self.signatures.len() >= threshold
+    }
+
+    /// Owners that have signed, in the order they will be packed.
+    pub fn owners(&self) -> impl Iterator<Item = &Address> {
+        self.signatures.keys()
+    }
+
+    /// Packs the collected signatures into the `signature` field of a SAFE
+    /// transaction request.
+    pub fn pack(&self) -> String {
+        let static_len = self.signatures.len() * 65;
+        let mut static_parts = Vec::with_capacity(static_len);
+        let mut dynamic_parts = Vec::new();
+
+        for (owner, signature) in &self.signatures {
+            let offset = static_len + dynamic_parts.len();
+            static_parts.extend_from_slice(&signature.static_part(owner, offset));
+
+            if let SafeSignature::Contract(bytes) = signature {
+                let mut len = [0u8; 32];
+                U256::from(bytes.len()).to_big_endian(&mut len);
+                dynamic_parts.extend_from_slice(&len);
+                dynamic_parts.extend_from_slice(bytes);
+            }
+        }
+
+        static_parts.extend_from_slice(&dynamic_parts);
+        format!("0x{}", hex::encode(static_parts))
+    }
+
+    fn check_owner(
+        &self,
+        owner: &Address,
+        recovered: &alloy_primitives::Address,
+    ) -> Result<(), RelayerClientException> {
+        if owner.to_alloy() != *recovered {
+            return Err(RelayerClientException::InvalidSignature(format!(
+                "signature was made by {}, not owner {}",
+                recovered,
+                owner.to_alloy()
+            )));
+        }
+        Ok(())
+    }
+}
+
+fn parse_signature(sig_hex: &str) -> Result<Signature, RelayerClientException> {
+    let sig_hex = sig_hex.strip_prefix("0x").unwrap_or(sig_hex);
+    let bytes = hex::decode(sig_hex)
+        .map_err(|e| RelayerClientException::encoding(format!("Invalid hex: {}", e)))?;
+    Signature::from_raw(&bytes).map_err(|e| RelayerClientException::InvalidSignature(e.to_string()))
+}
+
+#[test]
+fn test_single_eth_sign_matches_split_and_pack_sig() {
+    use crate::builder::safe::{create_safe_signature_sync, split_and_pack_sig};
+    use crate::conversion::ToEthers;
+    use alloy::signers::local::PrivateKeySigner;
+
+    let hash = "0xb067b60440424df66ce7491afa39f5ba1977ac0a8466cbb017280cc01304698f";
+    let signer: PrivateKeySigner =
+        "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80"
+            .parse()
+            .unwrap();
+    let sig = create_safe_signature_sync(&signer, hash).unwrap();
+
+    let mut collector = SafeSignatureCollector::new(hash).unwrap();
+    collector
+        .add_eth_sign(signer.address().to_ethers(), &sig)
+        .unwrap();
+
+    assert_eq!(collector.pack(), split_and_pack_sig(&sig).unwrap());
+    assert!(
+        collector
+            .add_eth_sign(Address::zero(), &sig)
+            .unwrap_err()
+            .to_string()
+            .contains("not owner")
+    );
+}
+
+#[test]
+fn test_pack_mixed_signatures_sorted_by_owner() {
+    use crate::builder::safe::create_safe_signature_sync;
+    use crate::conversion::ToEthers;
+    use alloy::signers::local::PrivateKeySigner;
+    use alloy_signer::SignerSync;
+    use std::str::FromStr;
+
+    let hash = "0xb067b60440424df66ce7491afa39f5ba1977ac0a8466cbb017280cc01304698f";
+    // 0xf39F... and 0x7099...
+    let first: PrivateKeySigner =
+        "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80"
+            .parse()
+            .unwrap();
+    let second: PrivateKeySigner =
+        "0x59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d"
+            .parse()
+            .unwrap();
+    let approver = Address::from_str("0x0000000000000000000000000000000000000001").unwrap();
+    let wallet = Address::from_str("0xffffffffffffffffffffffffffffffffffffffff").unwrap();
+
+    let mut collector = SafeSignatureCollector::new(hash).unwrap();
+    let eth_sign = create_safe_signature_sync(&first, hash).unwrap();
+    collector
+        .add_eth_sign(first.address().to_ethers(), &eth_sign)
+        .unwrap();
+    let eip712 = second
+        .sign_hash_sync(&B256::from(hash_from_hex(hash).unwrap()))
+        .unwrap();
+    collector
+        .add_eip712(second.address().to_ethers(), &eip712.to_string())
+        .unwrap();
+    collector.add_approved_hash(approver);
+    collector
+        .add_contract_signature(wallet, "0xdeadbeef")
+        .unwrap();
+
+    assert!(collector.has_threshold(4));
+    assert!(!collector.has_threshold(5));
+    assert_eq!(
+        collector.owners().cloned().collect::<Vec<_>>(),
+        vec![
+            approver,
+            second.address().to_ethers(),
+            first.address().to_ethers(),
+            wallet
+        ]
+    );
+
+    let packed = hex::decode(&collector.pack()[2..]).unwrap();
+    assert_eq!(packed.len(), 4 * 65 + 32 + 4);
+
+    // approved hash: r = owner, v = 1
+    assert_eq!(&packed[12..32], approver.as_bytes());
+    assert_eq!(packed[64], 1);
+    // EIP-712: v stays 27/28
+    assert_eq!(&packed[65..130], &eip712.as_bytes()[..]);
+    // eth_sign: v + 4
+    assert!(packed[194] == 31 || packed[194] == 32);
+    // contract: r = owner, s = offset of the dynamic part, v = 0
+    assert_eq!(&packed[207..227], wallet.as_bytes());
+    assert_eq!(U256::from_big_endian(&packed[227..259]), U256::from(260));
+    assert_eq!(packed[259], 0);
+    assert_eq!(U256::from_big_endian(&packed[260..292]), U256::from(4));
+    assert_eq!(&packed[292..], &[0xde, 0xad, 0xbe, 0xef]);
+}
@@ -1,38 +1,51 @@
 use crate::builder::{
-    build_safe_create_transaction_request, build_safe_transaction_request, derive,
+    build_proxy_transaction_request, build_safe_create_transaction_request,
+    build_safe_transaction_request, verify_safe_request,
 };
 use crate::config::{ContractConfig, get_contract_config};
-use crate::constants::ZERO_ADDRESS;
+use crate::encode::encode_proxy_transaction_data;
 use crate::endpoints::{
-    GET_DEPLOYED, GET_NONCE, GET_TRANSACTION, GET_TRANSACTIONS, SUBMIT_TRANSACTION,
+    GET_DEPLOYED, GET_NONCE, GET_RELAY_PAYLOAD, GET_TRANSACTION, GET_TRANSACTIONS,
+    SUBMIT_TRANSACTION,
 };
 use crate::errors::{RelayerApiException, RelayerClientException};
-use crate::http_helpers::RequestData;
+use crate::http_helpers::{RequestData, canonical_json};
 use crate::http_helpers::{get, post};
 use crate::models::{
-    SafeCreateTransactionArgs, SafeTransaction, SafeTransactionArgs, TransactionType,
+    DeployedResponse, NonceResponse, ProxyTransaction, ProxyTransactionArgs, RelayPayload,
+    RelayerTransaction, SafeCreateTransactionArgs, SafeGasParams, SafeTransaction,
+    SafeTransactionArgs, SubmitTransactionResponse, TransactionRequest, TransactionType,
 };
+use crate::nonce::{NonceManager, is_nonce_conflict, is_rejected_submission};
+use crate::polymarket::approvals::trading_approvals_for;
 use crate::response::ClientRelayerTransactionResponse;
+use crate::retry::RetryPolicy;
+use crate::transport::{RelayTransport, ReqwestTransport};
+use crate::watch::{TransactionOutcome, WatchEvent, WatchOptions, watch};
 use alloy::signers::Signer;
 use alloy::signers::local::PrivateKeySigner;
 use ethers::types::Address;
+use futures::{Stream, StreamExt};
 use polymarket_client_sdk::auth::{Kind, builder::Builder};
 use reqwest::header::HeaderMap;
 use reqwest::{Body, Method, Request};
-use serde_json::Value;
+use serde::de::DeserializeOwned;
 use std::str::FromStr;
-use std::thread;
+use std::sync::Arc;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
-use tokio::runtime::Runtime;
 use url::Url;
 
 use crate::conversion::ToEthers;
 pub struct RelayClient {
+    transport: Arc<dyn RelayTransport>,
     relayer_url: String,
     chain_id: u64,
     contract_config: ContractConfig,
-    signer: Option<PrivateKeySigner>,
+    signer: Option<Arc<dyn Signer + Send + Sync>>,
     builder_config: Option<Builder>,
+    retry_policy: RetryPolicy,
+    verify_signatures: bool,
+    nonces: NonceManager,
 }
 
 impl RelayClient {
@@ -42,331 +55,620 @@ impl RelayClient {
         private_key: Option<&str>,
         builder_config: Option<Builder>,
     ) -> Result<Self, RelayerClientException> {
-        let relayer_url = if relayer_url.ends_with('/') {
-            relayer_url[..relayer_url.len() - 1].to_string()
-        } else {
-            relayer_url.to_string()
-        };
-
         let contract_config = get_contract_config(chain_id)?;
+        Self::new_with_contract_config(
+            relayer_url,
+            chain_id,
+            contract_config,
+            private_key,
+            builder_config,
+        )
+    }
+
+    /// Like [`RelayClient::new`], with custom contracts instead of the
+    /// built-in configuration for `chain_id`, e.g. for a fork or devnet.
+    pub fn new_with_contract_config(
+        relayer_url: &str,
+        chain_id: u64,
+        contract_config: ContractConfig,
+        private_key: Option<&str>,
+        builder_config: Option<Builder>,
+    ) -> Result<Self, RelayerClientException> {
+        let relayer_url = relayer_url
+            .strip_suffix('/')
+            .unwrap_or(relayer_url)
+            .to_string();
+
+        contract_config.validate()?;
+        let transport = ReqwestTransport::new()?;
 
         let signer = if let Some(pk) = private_key {
-            let mut signer = pk.parse::<PrivateKeySigner>().unwrap();
+            let mut signer = pk
+                .parse::<PrivateKeySigner>()
+                .map_err(RelayerClientException::InvalidKey)?;
             signer.set_chain_id(Some(chain_id));
-            Some(signer)
+            Some(Arc::new(signer) as Arc<dyn Signer + Send + Sync>)
         } else {
             None
         };
 
         Ok(RelayClient {
+            transport: Arc::new(transport),
             relayer_url,
             chain_id,
             contract_config,
             signer,
             builder_config,
+            retry_policy: RetryPolicy::default(),
+            verify_signatures: false,
+            nonces: NonceManager::default(),
         })
     }
 
-    pub fn get_nonce(
+    /// Replaces the HTTP transport, e.g. with a custom client stack or a
+    /// test double.
+    pub fn with_transport(mut self, transport: impl RelayTransport + 'static) -> Self {
+        self.transport = Arc::new(transport);
+        self
+    }
+
+    /// Replaces the signer, e.g. with an AWS KMS key, an encrypted keystore
+    /// or a remote signing service.
+    pub fn with_signer(mut self, signer: impl Signer + Send + Sync + 'static) -> Self {
+        self.signer = Some(Arc::new(signer));
+        self
+    }
+
+    /// Replaces the retry policy used for relayer requests. Pass
+    /// [`RetryPolicy::none`] to make a single attempt.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// When enabled, [`RelayClient::execute`] checks that every request it
+    /// builds targets the signer's Safe and recovers to the signer, and
+    /// refuses to submit it otherwise, turning chain id, Safe address or `v`
+    /// mistakes into a local error instead of an opaque relayer rejection.
+    pub fn with_signature_verification(mut self, enabled: bool) -> Self {
+        self.verify_signatures = enabled;
+        self
+    }
+
+    /// Sets how long locally reserved Safe nonces are trusted before
+    /// [`RelayClient::execute`] reads `/nonce` again. `None` only resyncs
+    /// after the relayer rejects a nonce. Defaults to 60 seconds.
+    pub fn with_nonce_resync_interval(mut self, interval: Option<Duration>) -> Self {
+        self.nonces = NonceManager::new(interval);
+        self
+    }
+
+    /// The nonce manager [`RelayClient::execute`] reserves Safe nonces from.
+    pub fn nonce_manager(&self) -> &NonceManager {
+        &self.nonces
+    }
+
+    /// The contracts this client signs for.
+    pub fn contract_config(&self) -> &ContractConfig {
+        &self.contract_config
+    }
+
+    pub async fn get_nonce(
         &self,
         signer_address: &Address,
         signer_type: &str,
-    ) -> Result<Value, RelayerApiException> {
+    ) -> Result<NonceResponse, RelayerApiException> {
         let url = format!(
             "{}{}?address={:?}&type={}",
             self.relayer_url, GET_NONCE, signer_address, signer_type
         );
-        get(&url, None, None)
+        self._get_request(&url).await
     }
 
-    pub fn get_transaction(&self, transaction_id: &str) -> Result<Value, RelayerApiException> {
+    pub async fn get_relay_payload(
+        &self,
+        signer_address: &Address,
+        signer_type: &str,
+    ) -> Result<RelayPayload, RelayerApiException> {
+        let url = format!(
+            "{}{}?address={:?}&type={}",
+            self.relayer_url, GET_RELAY_PAYLOAD, signer_address, signer_type
+        );
+        self._get_request(&url).await
+    }
+
+    pub async fn get_transaction(
+        &self,
+        transaction_id: &str,
+    ) -> Result<Vec<RelayerTransaction>, RelayerApiException> {
         let url = format!(
             "{}{}?id={}",
             self.relayer_url, GET_TRANSACTION, transaction_id
         );
-        get(&url, None, None)
+        self._get_request(&url).await
     }
 
-    pub fn get_transactions(&self) -> Result<Value, RelayerApiException> {
+    pub async fn get_transactions(&self) -> Result<Vec<RelayerTransaction>, RelayerApiException> {
         let url = format!("{}{}", self.relayer_url, GET_TRANSACTIONS);
-        get(&url, None, None)
+        self._get_request(&url).await
     }
 
-    pub fn get_deployed(&self, safe_address: &Address) -> Result<bool, RelayerApiException> {
+    pub async fn get_deployed(&self, safe_address: &Address) -> Result<bool, RelayerApiException> {
         let url = format!(
             "{}{}?address={:?}",
             self.relayer_url, GET_DEPLOYED, safe_address
         );
-        let response = get(&url, None, None)?;
-
-        if let Some(deployed) = response.get("deployed") {
-            if let Some(deployed_bool) = deployed.as_bool() {
-                return Ok(deployed_bool);
-            }
-        }
-        Ok(false)
+        let response: DeployedResponse = self._get_request(&url).await?;
+        Ok(response.deployed)
     }
 
-    pub fn execute(
+    /// Executes `transactions` from the signer's Safe, batching them through
+    /// multisend when there is more than one. `gas_params` sets the refund
+    /// the Safe pays the relayer; `None` refunds nothing.
+    ///
+    /// The nonce is reserved from the client's [`NonceManager`], so
+    /// concurrent calls for the same Safe sign consecutive nonces.
+    /// When the submission provably did not reach the relayer, or was turned
+    /// away with a 4xx, the nonce is handed to the next caller. After an
+    /// ambiguous failure, such as a timeout, it is kept and the next call
+    /// resyncs from `/nonce` instead.
+    pub async fn execute(
         &self,
         transactions: &[SafeTransaction],
         metadata: Option<&str>,
-    ) -> Result<ClientRelayerTransactionResponse, RelayerClientException> {
+        gas_params: Option<&SafeGasParams>,
+    ) -> Result<ClientRelayerTransactionResponse<'_>, RelayerClientException> {
         self.assert_signer_needed()?;
         self.assert_builder_creds_needed()?;
 
         let safe_address = self.get_expected_safe()?;
 
-        let deployed = self.get_deployed(&safe_address).map_err(|e| {
-            RelayerClientException::new(format!("Failed to check deployment: {}", e))
-        })?;
+        let deployed = self.get_deployed(&safe_address).await?;
 
         if !deployed {
-            return Err(RelayerClientException::new(format!(
-                "expected safe {} is not deployed",
-                safe_address
-            )));
+            return Err(RelayerClientException::SafeNotDeployed(safe_address));
         }
 
-        let from_address = self.signer.as_ref().unwrap().address();
+        let from_address = self.signer()?.address().to_ethers();
+
+        // One retry with a resynced nonce if the relayer reports a conflict
+        let mut resynced = false;
+        loop {
+            let reservation = self
+                .nonces
+                .reserve(from_address, || async {
+                    self.get_nonce(&from_address, TransactionType::Safe.as_str())
+                        .await
+                        .map(|resp| resp.nonce)
+                        .map_err(RelayerClientException::NonceFetch)
+                })
+                .await?;
+            let nonce = reservation.nonce().to_string();
+
+            let txn_request = self
+                .build_execute_request(transactions, &nonce, metadata, gas_params)
+                .await?;
+
+            if self.verify_signatures {
+                verify_safe_request(
+                    &txn_request,
+                    &from_address,
+                    &self.contract_config,
+                    self.chain_id,
+                )?;
+            }
 
-        let nonce_payload = self
-            .get_nonce(&from_address.to_ethers(), TransactionType::Safe.as_str())
-            .map_err(|e| RelayerClientException::new(format!("Failed to get nonce: {}", e)))?;
+            match self.submit_signed(&txn_request).await {
+                Ok(resp) => {
+                    reservation.commit();
+                    return Ok(resp);
+                }
+                Err(e) if is_nonce_conflict(&e) => {
+                    self.nonces.invalidate(&from_address);
+                    if resynced {
+                        return Err(e);
+                    }
+                    resynced = true;
+                }
+                // Released on drop for the next caller
+                Err(e) if is_rejected_submission(&e) => return Err(e),
+                Err(e) => {
+                    // The relayer may have accepted it, so the nonce stays
+                    // used until `/nonce` says otherwise
+                    self.nonces.invalidate(&from_address);
+                    reservation.commit();
+                    return Err(e);
+                }
+            }
+        }
+    }
 
-        let nonce = nonce_payload
-            .get("nonce")
-            .and_then(|n| n.as_str())
-            .ok_or_else(|| RelayerClientException::new("invalid nonce payload received"))?
-            .to_string();
+    /// Signs a SAFE transaction request for `nonce` without any network
+    /// access. The result can be serialized, moved off an air-gapped
+    /// machine and later passed to [`RelayClient::submit_signed`].
+    pub async fn build_execute_request(
+        &self,
+        transactions: &[SafeTransaction],
+        nonce: &str,
+        metadata: Option<&str>,
+        gas_params: Option<&SafeGasParams>,
+    ) -> Result<TransactionRequest, RelayerClientException> {
+        self.assert_signer_needed()?;
+
+        let from_address = self.signer()?.address();
 
         let safe_args = SafeTransactionArgs {
             from_address: from_address.to_ethers(),
-            nonce: nonce.clone(),
+            nonce: nonce.to_string(),
             chain_id: self.chain_id,
             transactions: transactions.to_vec(),
+            gas_params: gas_params.cloned(),
         };
 
-        println!("none is {:?}", nonce);
-
-        let txn_request = build_safe_transaction_request(
-            self.signer.as_ref().unwrap(),
-            &safe_args,
-            &self.contract_config,
-            metadata,
-        )
-        .map_err(|e| RelayerClientException::new(format!("Failed to build transaction: {}", e)))?;
-
-        let resp = self._post_request(SUBMIT_TRANSACTION, &txn_request)?;
+        build_safe_transaction_request(self.signer()?, &safe_args, &self.contract_config, metadata)
+            .await
+    }
 
-        let transaction_id = resp
-            .get("transactionID")
-            .and_then(|v| v.as_str())
-            .map(|s| s.to_string());
-        let transaction_hash = resp
-            .get("transactionHash")
-            .and_then(|v| v.as_str())
-            .map(|s| s.to_string());
+    /// Grants the exchanges the collateral and Conditional Tokens approvals
+    /// needed to trade, in one multisend from the signer's Safe. See
+    /// [`trading_approvals_for`].
+    pub async fn setup_trading_approvals(
+        &self,
+    ) -> Result<ClientRelayerTransactionResponse<'_>, RelayerClientException> {
+        let approvals = trading_approvals_for(&self.contract_config)?;
+        self.execute(&approvals, Some("setup trading approvals"), None)
+            .await
+    }
 
-        Ok(ClientRelayerTransactionResponse::new(
-            transaction_id,
-            transaction_hash,
-            self,
-        ))
+    pub async fn deploy(
+        &self,
+    ) -> Result<ClientRelayerTransactionResponse<'_>, RelayerClientException> {
+        let zero_address = Address::zero();
+        self.deploy_with_payment(&zero_address, "0", &zero_address)
+            .await
     }
 
-    pub fn deploy(&self) -> Result<ClientRelayerTransactionResponse, RelayerClientException> {
+    /// Deploys the signer's Safe, paying `payment` base units of
+    /// `payment_token` to `payment_receiver` from the new Safe so a sponsor
+    /// can be reimbursed for the deployment.
+    pub async fn deploy_with_payment(
+        &self,
+        payment_token: &Address,
+        payment: &str,
+        payment_receiver: &Address,
+    ) -> Result<ClientRelayerTransactionResponse<'_>, RelayerClientException> {
         self.assert_signer_needed()?;
         self.assert_builder_creds_needed()?;
 
         let safe_address = self.get_expected_safe()?;
-        let deployed = self.get_deployed(&safe_address).map_err(|e| {
-            RelayerClientException::new(format!("Failed to check deployment: {}", e))
-        })?;
+        let deployed = self.get_deployed(&safe_address).await?;
 
         if deployed {
-            return Err(RelayerClientException::new(format!(
-                "safe {} is already deployed!",
-                safe_address
-            )));
+            return Err(RelayerClientException::SafeAlreadyDeployed(safe_address));
         }
 
-        let from_address = self.signer.as_ref().unwrap().address();
-        let zero_address = Address::from_str(ZERO_ADDRESS).unwrap();
+        let txn_request = self
+            .build_deploy_request_with_payment(payment_token, payment, payment_receiver)
+            .await?;
+
+        self.submit_signed(&txn_request).await
+    }
+
+    /// Signs the SAFE-CREATE request deploying the signer's Safe without any
+    /// network access.
+    pub async fn build_deploy_request(&self) -> Result<TransactionRequest, RelayerClientException> {
+        let zero_address = Address::zero();
+        self.build_deploy_request_with_payment(&zero_address, "0", &zero_address)
+            .await
+    }
+
+    /// Offline counterpart of [`RelayClient::deploy_with_payment`].
+    pub async fn build_deploy_request_with_payment(
+        &self,
+        payment_token: &Address,
+        payment: &str,
+        payment_receiver: &Address,
+    ) -> Result<TransactionRequest, RelayerClientException> {
+        self.assert_signer_needed()?;
+
+        let from_address = self.signer()?.address();
 
         let args = SafeCreateTransactionArgs {
             from_address: from_address.to_ethers(),
             chain_id: self.chain_id,
-            payment_token: zero_address,
-            payment: "0".to_string(),
-            payment_receiver: zero_address,
+            payment_token: *payment_token,
+            payment: payment.to_string(),
+            payment_receiver: *payment_receiver,
         };
 
-        let txn_request = build_safe_create_transaction_request(
-            self.signer.as_ref().unwrap(),
-            &args,
-            &self.contract_config,
-        )
-        .map_err(|e| {
-            RelayerClientException::new(format!("Failed to build create transaction: {}", e))
+        build_safe_create_transaction_request(self.signer()?, &args, &self.contract_config).await
+    }
+
+    /// Executes `transactions` through the signer's Polymarket proxy wallet.
+    ///
+    /// The proxy wallet is created by the factory on first use, so unlike
+    /// [`RelayClient::execute`] no deployment check is made.
+    pub async fn execute_proxy(
+        &self,
+        transactions: &[ProxyTransaction],
+        metadata: Option<&str>,
+    ) -> Result<ClientRelayerTransactionResponse<'_>, RelayerClientException> {
+        self.assert_signer_needed()?;
+        self.assert_builder_creds_needed()?;
+
+        let from_address = self.signer()?.address().to_ethers();
+
+        let relay_payload = self
+            .get_relay_payload(&from_address, TransactionType::Proxy.as_str())
+            .await?;
+
+        let txn_request = self
+            .build_proxy_request(transactions, &relay_payload, metadata)
+            .await?;
+
+        self.submit_signed(&txn_request).await
+    }
+
+    /// Signs a PROXY transaction request against a previously fetched
+    /// `/relay-payload` without any network access.
+    pub async fn build_proxy_request(
+        &self,
+        transactions: &[ProxyTransaction],
+        relay_payload: &RelayPayload,
+        metadata: Option<&str>,
+    ) -> Result<TransactionRequest, RelayerClientException> {
+        self.assert_signer_needed()?;
+        self.assert_proxy_supported()?;
+
+        let from_address = self.signer()?.address().to_ethers();
+        let relay = Address::from_str(&relay_payload.address).map_err(|e| {
+            RelayerClientException::encoding(format!("invalid relay address received: {}", e))
         })?;
 
-        let resp = self._post_request(SUBMIT_TRANSACTION, &txn_request)?;
+        let args = ProxyTransactionArgs {
+            from_address,
+            nonce: relay_payload.nonce.clone(),
+            gas_price: "0".to_string(),
+            gas_limit: None,
+            data: encode_proxy_transaction_data(transactions)?,
+            relay,
+        };
+
+        build_proxy_transaction_request(self.signer()?, &args, &self.contract_config, metadata)
+            .await
+    }
+
+    /// Submits an already signed request, attaching only the builder
+    /// authentication headers. No signer is needed.
+    pub async fn submit_signed(
+        &self,
+        request: &TransactionRequest,
+    ) -> Result<ClientRelayerTransactionResponse<'_>, RelayerClientException> {
+        self.assert_builder_creds_needed()?;
 
-        let transaction_id = resp
-            .get("transactionID")
-            .and_then(|v| v.as_str())
-            .map(|s| s.to_string());
-        let transaction_hash = resp
-            .get("transactionHash")
-            .and_then(|v| v.as_str())
-            .map(|s| s.to_string());
+        let resp = self._post_request(SUBMIT_TRANSACTION, request).await?;
 
         Ok(ClientRelayerTransactionResponse::new(
-            transaction_id,
-            transaction_hash,
+            resp.transaction_id,
+            resp.transaction_hash,
             self,
         ))
     }
 
-    pub fn poll_until_state(
+    /// Follows `transaction_id` until it is mined (or confirmed), fails, is
+    /// invalidated, or `options.deadline` passes. Every state change is
+    /// yielded, followed by a single [`WatchEvent::Finished`].
+    pub fn watch(
+        &self,
+        transaction_id: &str,
+        options: WatchOptions,
+    ) -> impl Stream<Item = Result<WatchEvent, RelayerApiException>> + Send + '_ {
+        watch(self, transaction_id, options)
+    }
+
+    /// Drives [`RelayClient::watch`] to completion and returns the outcome.
+    pub async fn wait_for_outcome(
+        &self,
+        transaction_id: &str,
+        options: WatchOptions,
+    ) -> Result<TransactionOutcome, RelayerApiException> {
+        let events = self.watch(transaction_id, options);
+        futures::pin_mut!(events);
+        while let Some(event) = events.next().await {
+            if let WatchEvent::Finished(outcome) = event? {
+                return Ok(outcome);
+            }
+        }
+        Err(RelayerApiException::InvalidResponse(
+            "watch ended without an outcome".to_string(),
+        ))
+    }
+
+    /// Polls until the transaction reaches one of `states`. `None` is
+    /// returned both when it reaches `fail_state` and when `max_polls` run
+    /// out; [`RelayClient::wait_for_outcome`] tells these apart.
+    pub async fn poll_until_state(
         &self,
         transaction_id: &str,
         states: &[&str],
         fail_state: Option<&str>,
         max_polls: Option<usize>,
         poll_frequency: Option<u64>,
-    ) -> Result<Option<Value>, RelayerApiException> {
+    ) -> Result<Option<RelayerTransaction>, RelayerApiException> {
         let target_states: std::collections::HashSet<&str> = states.iter().cloned().collect();
         let poll_limit = max_polls.unwrap_or(10);
         let poll_frequency_ms = poll_frequency.unwrap_or(2000).max(1000);
 
-        println!(
-            "Waiting for transaction {} matching states: {:?}...",
-            transaction_id, target_states
+        log::debug!(
+            "Waiting for transaction {} matching states: {:?}",
+            transaction_id,
+            target_states
         );
 
         for _ in 0..poll_limit {
-            let transactions = self.get_transaction(transaction_id)?;
-
-            if let Some(txn_array) = transactions.as_array() {
-                if let Some(txn) = txn_array.first() {
-                    if let Some(txn_state) = txn.get("state").and_then(|s| s.as_str()) {
-                        if target_states.contains(txn_state) {
-                            return Ok(Some(txn.clone()));
-                        }
-                        if let Some(fail) = fail_state {
-                            if txn_state == fail {
-                                let txn_hash = txn
-                                    .get("transactionHash")
-                                    .and_then(|v| v.as_str())
-                                    .unwrap_or("unknown");
-                                eprintln!(
-                                    "txn {} failed onchain, transaction_hash: {}!",
-                                    transaction_id, txn_hash
-                                );
-                                return Ok(None);
-                            }
-                        }
-                    }
+            let transactions = self.get_transaction(transaction_id).await?;
+
+            if let Some(txn) = transactions.into_iter().next() {
+                let txn_state = txn.state.as_str();
+                if target_states.contains(txn_state) {
+                    return Ok(Some(txn));
+                }
+                if fail_state == Some(txn_state) {
+                    log::warn!(
+                        "Transaction {} failed onchain, transaction_hash: {}",
+                        transaction_id,
+                        txn.transaction_hash.as_deref().unwrap_or("unknown")
+                    );
+                    return Ok(None);
                 }
             }
 
-            thread::sleep(Duration::from_millis(poll_frequency_ms));
+            tokio::time::sleep(Duration::from_millis(poll_frequency_ms)).await;
         }
 
-        println!(
-            "Transaction {} not found or not in given states, timing out!",
+        log::warn!(
+            "Transaction {} not found or not in given states, timing out",
             transaction_id
         );
         Ok(None)
     }
 
-    fn _post_request(
+    async fn _get_request<T: DeserializeOwned>(&self, url: &str) -> Result<T, RelayerApiException> {
+        get(self.transport.as_ref(), &self.retry_policy, url, None, None).await
+    }
+
+    async fn _post_request(
         &self,
         request_path: &str,
-        body: &crate::models::TransactionRequest,
-    ) -> Result<Value, RelayerClientException> {
-        let builder_headers = self._generate_builder_headers("POST", request_path, Some(body))?;
+        body: &TransactionRequest,
+    ) -> Result<SubmitTransactionResponse, RelayerClientException> {
+        // Serialized once so the signed payload and the sent body are identical
+        let body = canonical_json(body).map_err(|e| {
+            RelayerClientException::encoding(format!("Invalid request body: {}", e))
+        })?;
+        let builder_headers = self
+            ._generate_builder_headers("POST", request_path, Some(&body))
+            .await?;
         let url = format!("{}{}", self.relayer_url, request_path);
 
         post(
+            self.transport.as_ref(),
+            &self.retry_policy,
             &url,
             Some(builder_headers),
-            Some(&RequestData::TransactionRequest(body.clone())),
+            Some(&RequestData::Canonical(body)),
         )
-        .map_err(|e| RelayerClientException::new(format!("API request failed: {}", e)))
+        .await
+        .map_err(RelayerClientException::from)
     }
 
-    fn _generate_builder_headers(
+    async fn _generate_builder_headers(
         &self,
         method: &str,
         request_path: &str,
-        body: Option<&crate::models::TransactionRequest>,
+        body: Option<&str>,
     ) -> Result<HeaderMap, RelayerClientException> {
-        let body_str = body.map(|b| {
-            serde_json::to_string(b)
-                .unwrap_or_default()
-                .replace("\"", "'")
-                .replace(":'", ": '")
-                .replace(",'", ", '")
-                .replace(":{", ": {")
-        });
         let request_url = format!("{}{}", self.relayer_url, request_path);
-        let mut request = Request::new(
-            Method::from_str(method).unwrap(),
-            Url::parse(&request_url).unwrap(),
-        );
+        let method = Method::from_str(method).map_err(|e| {
+            RelayerClientException::BuilderAuth(format!("Invalid method {}: {}", method, e))
+        })?;
+        let url = Url::parse(&request_url).map_err(|e| {
+            RelayerClientException::BuilderAuth(format!(
+                "Invalid relayer URL {}: {}",
+                request_url, e
+            ))
+        })?;
+        let mut request = Request::new(method, url);
         request
             .body_mut()
-            .replace(Body::from(body_str.clone().unwrap_or_default()));
-        let rt = Runtime::new().unwrap();
+            .replace(Body::from(body.unwrap_or_default().to_string()));
         ///// Timestamp in seconds since [`std::time::UNIX_EPOCH`]
-        let headers = rt
-            .block_on(
-                self.builder_config.as_ref().unwrap().extra_headers(
-                    &request,
-                    SystemTime::now()
-                        .duration_since(UNIX_EPOCH)
-                        .map_err(|e| {
-                            RelayerClientException::new(format!(
-                                "Failed to get duration since UNIX_EPOCH: {}",
-                                e
-                            ))
-                        })?
-                        .as_secs() as i64,
-                ),
-            )
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
             .map_err(|e| {
-                RelayerClientException::new(format!("Failed to generate builder headers: {}", e))
+                RelayerClientException::BuilderAuth(format!(
+                    "Failed to get duration since UNIX_EPOCH: {}",
+                    e
+                ))
+            })?
+            .as_secs() as i64;
+        let headers = self
+            .builder_config
+            .as_ref()
+            .ok_or(RelayerClientException::MissingBuilderCredentials)?
+            .extra_headers(&request, timestamp)
+            .await
+            .map_err(|e| {
+                RelayerClientException::BuilderAuth(format!(
+                    "Failed to generate builder headers: {}",
+                    e
+                ))
             })?;
         Ok(headers)
     }
 
     pub fn get_expected_safe(&self) -> Result<Address, RelayerClientException> {
         self.assert_signer_needed()?;
-        let addr = self.signer.as_ref().unwrap().address();
-        Ok(derive(
-            &addr.to_ethers(),
-            &self.contract_config.safe_factory,
-        ))
+        let addr = self.signer()?.address();
+        self.contract_config.safe_address(&addr.to_ethers())
+    }
+
+    pub fn get_expected_proxy_wallet(&self) -> Result<Address, RelayerClientException> {
+        self.assert_signer_needed()?;
+        self.assert_proxy_supported()?;
+        let addr = self.signer()?.address();
+        self.contract_config.proxy_wallet_address(&addr.to_ethers())
+    }
+
+    fn signer(&self) -> Result<&(dyn Signer + Send + Sync), RelayerClientException> {
+        self.signer
+            .as_deref()
+            .ok_or(RelayerClientException::MissingSigner)
     }
 
     fn assert_signer_needed(&self) -> Result<(), RelayerClientException> {
-        if self.signer.is_none() {
-            return Err(RelayerClientException::new(
-                "signer is required for this endpoint",
-            ));
-        }
-        Ok(())
+        self.signer().map(|_| ())
     }
 
     fn assert_builder_creds_needed(&self) -> Result<(), RelayerClientException> {
         if self.builder_config.is_none() {
-            return Err(RelayerClientException::new(
-                "builder credentials are required for this endpoint",
-            ));
+            return Err(RelayerClientException::MissingBuilderCredentials);
         }
         Ok(())
     }
+
+    fn assert_proxy_supported(&self) -> Result<(), RelayerClientException> {
+        let config = &self.contract_config;
+        if config.proxy_factory.is_none() || config.relay_hub.is_none() {
+            return Err(RelayerClientException::UnsupportedChain(self.chain_id));
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_new_rejects_invalid_input() {
+    assert!(matches!(
+        RelayClient::new("http://relayer.test", 137, Some("0xnot-a-key"), None),
+        Err(RelayerClientException::InvalidKey(_))
+    ));
+    assert!(matches!(
+        RelayClient::new("http://relayer.test", 1, None, None),
+        Err(RelayerClientException::UnsupportedChain(1))
+    ));
+}
+
+#[test]
+fn test_proxy_wallets_are_unsupported_on_amoy() {
+    let client = RelayClient::new(
+        "http://relayer.test",
+        80002,
+        Some("0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80"),
+        None,
+    )
+    .unwrap();
+    assert!(matches!(
+        client.get_expected_proxy_wallet(),
+        Err(RelayerClientException::UnsupportedChain(80002))
+    ));
 }
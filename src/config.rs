@@ -1,11 +1,125 @@
+use crate::builder::derive::get_create2_address;
+use crate::constants::{PROXY_INIT_CODE_HASH, SAFE_FACTORY_NAME, SAFE_INIT_CODE_HASH};
 use crate::errors::RelayerClientException;
+use crate::utils::decode_hex;
+use ethabi::Token;
+use ethers::core::utils::keccak256;
 use ethers::types::Address;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::Path;
 
-#[derive(Debug, Clone)]
+/// Contracts the relayer client works with on one chain.
+///
+/// The built-in Polygon and Amoy configurations are returned by
+/// [`get_contract_config`]. Forks and devnets with their own deployments can
+/// build one with [`ContractConfig::new`] or load them with
+/// [`load_contract_configs`], and pass it to
+/// [`crate::RelayClient::new_with_contract_config`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ContractConfig {
     pub safe_factory: Address,
     pub safe_multisend: Address,
+    /// Keccak hash of the Safe proxy creation code used by `safe_factory`.
+    #[serde(default = "default_safe_init_code_hash")]
+    pub safe_init_code_hash: String,
+    /// EIP-712 domain name `safe_factory` verifies SAFE-CREATE signatures with.
+    #[serde(default = "default_safe_factory_name")]
+    pub safe_factory_name: String,
+    /// `ProxyWalletFactory`, if proxy wallets are deployed on this chain.
+    #[serde(default)]
+    pub proxy_factory: Option<Address>,
+    /// Keccak hash of the proxy wallet creation code used by `proxy_factory`.
+    #[serde(default = "default_proxy_init_code_hash")]
+    pub proxy_init_code_hash: String,
+    #[serde(default)]
+    pub relay_hub: Option<Address>,
+    /// Collateral ERC-20 of the markets (USDC.e on Polygon).
+    #[serde(default)]
+    pub collateral: Option<Address>,
+    /// Gnosis `ConditionalTokens`.
+    #[serde(default)]
+    pub conditional_tokens: Option<Address>,
+    #[serde(default)]
+    pub ctf_exchange: Option<Address>,
+    #[serde(default)]
+    pub neg_risk_ctf_exchange: Option<Address>,
+    #[serde(default)]
+    pub neg_risk_adapter: Option<Address>,
+}
+
+fn default_safe_init_code_hash() -> String {
+    SAFE_INIT_CODE_HASH.to_string()
+}
+
+fn default_safe_factory_name() -> String {
+    SAFE_FACTORY_NAME.to_string()
+}
+
+fn default_proxy_init_code_hash() -> String {
+    PROXY_INIT_CODE_HASH.to_string()
+}
+
+impl ContractConfig {
+    /// A configuration with the given Safe factory and multisend, Polymarket's
+    /// Safe init code hash and factory name, and no other contracts.
+    pub fn new(safe_factory: Address, safe_multisend: Address) -> Self {
+        ContractConfig {
+            safe_factory,
+            safe_multisend,
+            safe_init_code_hash: default_safe_init_code_hash(),
+            safe_factory_name: default_safe_factory_name(),
+            proxy_factory: None,
+            proxy_init_code_hash: default_proxy_init_code_hash(),
+            relay_hub: None,
+            collateral: None,
+            conditional_tokens: None,
+            ctf_exchange: None,
+            neg_risk_ctf_exchange: None,
+            neg_risk_adapter: None,
+        }
+    }
+
+    /// Checks the init code hashes and that the Safe contracts are set.
+    pub fn validate(&self) -> Result<(), RelayerClientException> {
+        if self.safe_factory.is_zero() || self.safe_multisend.is_zero() {
+            return Err(RelayerClientException::InvalidConfig(
+                "safe_factory and safe_multisend must be set".to_string(),
+            ));
+        }
+        for (name, hash) in [
+            ("safe_init_code_hash", &self.safe_init_code_hash),
+            ("proxy_init_code_hash", &self.proxy_init_code_hash),
+        ] {
+            let len = decode_hex(hash, name)
+                .map_err(|e| RelayerClientException::InvalidConfig(e.to_string()))?
+                .len();
+            if len != 32 {
+                return Err(RelayerClientException::InvalidConfig(format!(
+                    "{} must be 32 bytes, got {}",
+                    name, len
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Counterfactual address of the Safe `owner` gets from `safe_factory`.
+    pub fn safe_address(&self, owner: &Address) -> Result<Address, RelayerClientException> {
+        let salt = keccak256(ethabi::encode(&[Token::Address(*owner)]));
+        get_create2_address(&self.safe_init_code_hash, &self.safe_factory, &salt)
+    }
+
+    /// Address of the proxy wallet `owner` gets from `proxy_factory`.
+    pub fn proxy_wallet_address(&self, owner: &Address) -> Result<Address, RelayerClientException> {
+        let proxy_factory = self.proxy_factory.ok_or_else(|| {
+            RelayerClientException::InvalidConfig(
+                "proxy_factory is required for proxy wallets".to_string(),
+            )
+        })?;
+        let salt = keccak256(owner.as_bytes());
+        get_create2_address(&self.proxy_init_code_hash, &proxy_factory, &salt)
+    }
 }
 
 lazy_static::lazy_static! {
@@ -14,32 +128,197 @@ lazy_static::lazy_static! {
         map.insert(
             137,
             ContractConfig {
-                safe_factory: "0xaacFeEa03eb1561C4e67d661e40682Bd20E3541b"
-                    .parse()
-                    .unwrap(),
-                safe_multisend: "0xA238CBeb142c10Ef7Ad8442C6D1f9E89e07e7761"
-                    .parse()
-                    .unwrap(),
+                proxy_factory: Some(
+                    "0xaB45c5A4B0c941a2F231C04C3f49182e1A254052"
+                        .parse()
+                        .unwrap(),
+                ),
+                relay_hub: Some(
+                    "0xD216153c06E857cD7f72665E0aF1d7D82172F494"
+                        .parse()
+                        .unwrap(),
+                ),
+                collateral: Some(
+                    "0x2791Bca1f2de4661ED88A30C99A7a9449Aa84174"
+                        .parse()
+                        .unwrap(),
+                ),
+                conditional_tokens: Some(
+                    "0x4D97DCd97eC945f40cF65F87097ACe5EA0476045"
+                        .parse()
+                        .unwrap(),
+                ),
+                ctf_exchange: Some(
+                    "0x4bFb41d5B3570DeFd03C39a9A4D8dE6Bd8B8982E"
+                        .parse()
+                        .unwrap(),
+                ),
+                neg_risk_ctf_exchange: Some(
+                    "0xC5d563A36AE78145C45a50134d48A1215220f80a"
+                        .parse()
+                        .unwrap(),
+                ),
+                neg_risk_adapter: Some(
+                    "0xd91E80cF2E7be2e162c6513ceD06f1dD0dA35296"
+                        .parse()
+                        .unwrap(),
+                ),
+                ..ContractConfig::new(
+                    "0xaacFeEa03eb1561C4e67d661e40682Bd20E3541b"
+                        .parse()
+                        .unwrap(),
+                    "0xA238CBeb142c10Ef7Ad8442C6D1f9E89e07e7761"
+                        .parse()
+                        .unwrap(),
+                )
             },
         );
         map.insert(
             80002,
             ContractConfig {
-                safe_factory: "0xaacFeEa03eb1561C4e67d661e40682Bd20E3541b"
-                    .parse()
-                    .unwrap(),
-                safe_multisend: "0xA238CBeb142c10Ef7Ad8442C6D1f9E89e07e7761"
-                    .parse()
-                    .unwrap(),
+                collateral: Some(
+                    "0x9c4e1703476e875070ee25b56a58b008cfb8fa78"
+                        .parse()
+                        .unwrap(),
+                ),
+                conditional_tokens: Some(
+                    "0x69308FB512518e39F9b16112fA8d994F4e2Bf8bB"
+                        .parse()
+                        .unwrap(),
+                ),
+                ctf_exchange: Some(
+                    "0xdFE02Eb6733538f8Ea35D585af8DE5958AD99E40"
+                        .parse()
+                        .unwrap(),
+                ),
+                ..ContractConfig::new(
+                    "0xaacFeEa03eb1561C4e67d661e40682Bd20E3541b"
+                        .parse()
+                        .unwrap(),
+                    "0xA238CBeb142c10Ef7Ad8442C6D1f9E89e07e7761"
+                        .parse()
+                        .unwrap(),
+                )
             },
         );
         map
     };
 }
 
+/// The built-in configuration for `chain_id`.
 pub fn get_contract_config(chain_id: u64) -> Result<ContractConfig, RelayerClientException> {
     CONFIG
         .get(&chain_id)
         .cloned()
-        .ok_or_else(|| RelayerClientException::new(format!("Invalid chainID: {}", chain_id)))
+        .ok_or(RelayerClientException::UnsupportedChain(chain_id))
+}
+
+/// Parses configurations keyed by chain id from a JSON object, e.g.
+/// `{"31337": {"safe_factory": "0x…", "safe_multisend": "0x…"}}`.
+pub fn parse_contract_configs_json(
+    json: &str,
+) -> Result<HashMap<u64, ContractConfig>, RelayerClientException> {
+    let configs: HashMap<String, ContractConfig> = serde_json::from_str(json)
+        .map_err(|e| RelayerClientException::InvalidConfig(e.to_string()))?;
+    by_chain_id(configs)
+}
+
+/// Parses configurations keyed by chain id from TOML, one table per chain:
+///
+/// ```toml
+/// [31337]
+/// safe_factory = "0x…"
+/// safe_multisend = "0x…"
+/// ```
+pub fn parse_contract_configs_toml(
+    toml: &str,
+) -> Result<HashMap<u64, ContractConfig>, RelayerClientException> {
+    let configs: HashMap<String, ContractConfig> =
+        toml::from_str(toml).map_err(|e| RelayerClientException::InvalidConfig(e.to_string()))?;
+    by_chain_id(configs)
+}
+
+/// Loads configurations from a `.toml` or `.json` file, see
+/// [`parse_contract_configs_toml`] and [`parse_contract_configs_json`].
+pub fn load_contract_configs(
+    path: impl AsRef<Path>,
+) -> Result<HashMap<u64, ContractConfig>, RelayerClientException> {
+    let path = path.as_ref();
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| RelayerClientException::InvalidConfig(format!("{}: {}", path.display(), e)))?;
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("toml") => parse_contract_configs_toml(&contents),
+        Some("json") => parse_contract_configs_json(&contents),
+        _ => Err(RelayerClientException::InvalidConfig(format!(
+            "{}: expected a .toml or .json file",
+            path.display()
+        ))),
+    }
+}
+
+fn by_chain_id(
+    configs: HashMap<String, ContractConfig>,
+) -> Result<HashMap<u64, ContractConfig>, RelayerClientException> {
+    configs
+        .into_iter()
+        .map(|(chain_id, config)| {
+            let chain_id = chain_id.parse::<u64>().map_err(|e| {
+                RelayerClientException::InvalidConfig(format!(
+                    "invalid chain id {}: {}",
+                    chain_id, e
+                ))
+            })?;
+            config.validate()?;
+            Ok((chain_id, config))
+        })
+        .collect()
+}
+
+#[test]
+fn test_load_contract_configs() {
+    let toml = r#"
+        [31337]
+        safe_factory = "0x5FbDB2315678afecb367f032d93F642f64180aa3"
+        safe_multisend = "0xe7f1725E7734CE288F8367e1Bb143E90bb3F0512"
+        safe_init_code_hash = "0x1111111111111111111111111111111111111111111111111111111111111111"
+        safe_factory_name = "Devnet Proxy Factory"
+        collateral = "0x9fE46736679d2D9a65F0992F2272dE9f3c7fa6e0"
+    "#;
+    let configs = parse_contract_configs_toml(toml).unwrap();
+    let devnet = &configs[&31337];
+    assert_eq!(devnet.safe_factory_name, "Devnet Proxy Factory");
+    assert_eq!(devnet.proxy_init_code_hash, PROXY_INIT_CODE_HASH);
+    assert!(devnet.proxy_factory.is_none());
+
+    // The same configuration round-trips through JSON
+    let json = serde_json::json!({ "31337": devnet }).to_string();
+    assert_eq!(parse_contract_configs_json(&json).unwrap()[&31337], *devnet);
+
+    // A different init code hash moves the Safe
+    let owner = Address::repeat_byte(1);
+    let default_hash = ContractConfig::new(devnet.safe_factory, devnet.safe_multisend);
+    assert_ne!(
+        devnet.safe_address(&owner).unwrap(),
+        default_hash.safe_address(&owner).unwrap()
+    );
+    let polygon = get_contract_config(137).unwrap();
+    assert_eq!(
+        polygon.safe_address(&owner).unwrap(),
+        "0xce2de097d7dd859947ba4ca4e258db97aabcae0a"
+            .parse::<Address>()
+            .unwrap()
+    );
+
+    assert!(matches!(
+        parse_contract_configs_toml(
+            "[devnet]\nsafe_factory = \"0x5FbDB2315678afecb367f032d93F642f64180aa3\"\nsafe_multisend = \"0xe7f1725E7734CE288F8367e1Bb143E90bb3F0512\""
+        ),
+        Err(RelayerClientException::InvalidConfig(_))
+    ));
+    assert!(matches!(
+        parse_contract_configs_json(
+            r#"{"1": {"safe_factory": "0x5FbDB2315678afecb367f032d93F642f64180aa3", "safe_multisend": "0xe7f1725E7734CE288F8367e1Bb143E90bb3F0512", "safe_init_code_hash": "0x1234"}}"#
+        ),
+        Err(RelayerClientException::InvalidConfig(_))
+    ));
 }
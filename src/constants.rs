@@ -1,6 +1,11 @@
 pub const SAFE_INIT_CODE_HASH: &str =
     "0x2bce2127ff07fb632d16c8347c4ebf501f4841168bed00d9e6ef715ddb6fcecf";
 
+pub const PROXY_INIT_CODE_HASH: &str =
+    "0xd21df8dc65880a8606f09fe0ce3df9b8869287ab0b058be05aa9e8af6330a00b";
+
+pub const DEFAULT_PROXY_GAS_LIMIT: &str = "10000000";
+
 pub const ZERO_ADDRESS: &str = "0x0000000000000000000000000000000000000000";
 
 pub const SAFE_FACTORY_NAME: &str = "Polymarket Contract Proxy Factory";
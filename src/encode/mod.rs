@@ -1,4 +1,22 @@
+pub mod proxy;
 pub mod safe;
 
-pub use safe::create_safe_multisend_transaction;
+pub use proxy::encode_proxy_transaction_data;
+pub use safe::{create_safe_multisend_transaction, decode_multisend};
 
+use crate::models::{OperationType, SafeTransaction};
+use ethabi::Token;
+use ethers::core::utils::keccak256;
+use ethers::types::Address;
+
+/// A call of `signature` on `to` with ABI-encoded `args` and no value.
+pub(crate) fn contract_call(to: Address, signature: &str, args: &[Token]) -> SafeTransaction {
+    let mut data = keccak256(signature.as_bytes())[..4].to_vec();
+    data.extend_from_slice(&ethabi::encode(args));
+    SafeTransaction {
+        to,
+        operation: OperationType::Call,
+        data: format!("0x{}", hex::encode(data)),
+        value: "0".to_string(),
+    }
+}
@@ -0,0 +1,74 @@
+use crate::errors::RelayerClientException;
+use crate::models::ProxyTransaction;
+use crate::utils::{decode_hex, parse_u256};
+use ethabi::ethereum_types::U256;
+use ethabi::{Token, encode};
+use ethers::core::utils::keccak256;
+
+/// Encodes `proxy((uint8,address,uint256,bytes)[])` calldata for the
+/// `ProxyWalletFactory`.
+pub fn encode_proxy_transaction_data(
+    txns: &[ProxyTransaction],
+) -> Result<String, RelayerClientException> {
+    let calls = txns
+        .iter()
+        .map(|tx| {
+            Ok(Token::Tuple(vec![
+                Token::Uint(U256::from(tx.type_code.as_u8())),
+                Token::Address(tx.to),
+                Token::Uint(parse_u256(&tx.value, "value")?),
+                Token::Bytes(decode_hex(&tx.data, "data")?),
+            ]))
+        })
+        .collect::<Result<_, RelayerClientException>>()?;
+
+    let function_selector = &keccak256("proxy((uint8,address,uint256,bytes)[])".as_bytes())[..4];
+    let mut full_data = function_selector.to_vec();
+    full_data.extend_from_slice(&encode(&[Token::Array(calls)]));
+
+    Ok(format!("0x{}", hex::encode(&full_data)))
+}
+
+#[test]
+fn test_encode_proxy_transaction_data() {
+    use crate::models::CallType;
+    use std::str::FromStr;
+
+    let data = encode_proxy_transaction_data(&[ProxyTransaction {
+        to: ethers::types::Address::from_str("0x2791Bca1f2de4661ED88A30C99A7a9449Aa84174").unwrap(),
+        type_code: CallType::Call,
+        data: "0xdeadbeef".to_string(),
+        value: "0".to_string(),
+    }])
+    .unwrap();
+
+    assert_eq!(
+        data,
+        concat!(
+            "0x34ee9791",
+            "0000000000000000000000000000000000000000000000000000000000000020",
+            "0000000000000000000000000000000000000000000000000000000000000001",
+            "0000000000000000000000000000000000000000000000000000000000000020",
+            "0000000000000000000000000000000000000000000000000000000000000001",
+            "0000000000000000000000002791bca1f2de4661ed88a30c99a7a9449aa84174",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "0000000000000000000000000000000000000000000000000000000000000080",
+            "0000000000000000000000000000000000000000000000000000000000000004",
+            "deadbeef00000000000000000000000000000000000000000000000000000000",
+        )
+    );
+}
+
+#[test]
+fn test_encode_proxy_transaction_data_rejects_bad_hex() {
+    use crate::models::CallType;
+
+    let result = encode_proxy_transaction_data(&[ProxyTransaction {
+        to: ethers::types::Address::zero(),
+        type_code: CallType::Call,
+        data: "0xnothex".to_string(),
+        value: "0".to_string(),
+    }]);
+
+    assert!(matches!(result, Err(RelayerClientException::Encoding(_))));
+}
@@ -1,8 +1,13 @@
+use crate::errors::RelayerClientException;
 use crate::models::{OperationType, SafeTransaction};
+use crate::utils::{decode_hex, parse_u256};
 use ethabi::ethereum_types::U256;
 use ethabi::{encode, Token};
 use ethers::types::Address;
 
+/// keccak(text="multiSend(bytes)")[:4]
+pub const MULTISEND_SELECTOR: [u8; 4] = [0x8d, 0x80, 0xff, 0x0a];
+
 /// Manually pack data according to eth_abi.packed.encode_packed format
 /// Format: [uint8, address, uint256, uint256, bytes]
 /// - uint8: 1 byte (no padding)
@@ -44,21 +49,18 @@ fn encode_packed(
 pub fn create_safe_multisend_transaction(
     txns: &[SafeTransaction],
     safe_multisend_address: &Address,
-) -> SafeTransaction {
-    if txns.len() == 1 {
-        return txns[0].clone();
+) -> Result<SafeTransaction, RelayerClientException> {
+    match txns {
+        [] => return Err(RelayerClientException::encoding("no transactions to send")),
+        [txn] => return Ok(txn.clone()),
+        _ => {}
     }
 
     let mut encoded_txns = Vec::new();
 
     for tx in txns {
-        let data_bytes = if tx.data.starts_with("0x") {
-            hex::decode(&tx.data[2..]).unwrap_or_default()
-        } else {
-            hex::decode(&tx.data).unwrap_or_default()
-        };
-
-        let value = U256::from_dec_str(&tx.value).unwrap_or_default();
+        let data_bytes = decode_hex(&tx.data, "data")?;
+        let value = parse_u256(&tx.value, "value")?;
         let data_len = U256::from(data_bytes.len());
 
         // Pack: [uint8, address, uint256, uint256, bytes]
@@ -69,15 +71,199 @@ pub fn create_safe_multisend_transaction(
     let concatenated_txns: Vec<u8> = encoded_txns.into_iter().flatten().collect();
     let multisend_data = encode(&[Token::Bytes(concatenated_txns)]);
 
-    // keccak(text="multiSend(bytes)")[:4] = 0x8d80ff0a
-    let function_selector = hex::decode("8d80ff0a").unwrap();
-    let mut full_data = function_selector;
+    let mut full_data = MULTISEND_SELECTOR.to_vec();
     full_data.extend_from_slice(&multisend_data);
 
-    SafeTransaction {
+    Ok(SafeTransaction {
         to: *safe_multisend_address,
         operation: OperationType::DelegateCall,
         data: format!("0x{}", hex::encode(&full_data)),
         value: "0".to_string(),
+    })
+}
+
+/// Length of a packed entry without its data: operation, to, value, dataLen.
+const PACKED_HEADER_LEN: usize = 1 + 20 + 32 + 32;
+
+fn invalid_multisend(msg: impl std::fmt::Display) -> RelayerClientException {
+    RelayerClientException::encoding(format!("invalid multiSend calldata: {}", msg))
+}
+
+/// Reads a big-endian word that has to fit in `usize`.
+fn read_len(word: &[u8]) -> Result<usize, RelayerClientException> {
+    let value = U256::from_big_endian(word);
+    if value > U256::from(usize::MAX) {
+        return Err(invalid_multisend(format!("length {} out of range", value)));
+    }
+    Ok(value.as_usize())
+}
+
+/// Recovers the calls packed by [`create_safe_multisend_transaction`] from
+/// `multiSend(bytes)` calldata.
+///
+/// The selector, the ABI `bytes` wrapper (offset, length and zero padding)
+/// and every packed `[operation, to, value, dataLen, data]` entry are
+/// checked; truncated input and trailing bytes are errors.
+pub fn decode_multisend(data: &str) -> Result<Vec<SafeTransaction>, RelayerClientException> {
+    let calldata = decode_hex(data, "data")?;
+    let Some(args) = calldata.strip_prefix(&MULTISEND_SELECTOR) else {
+        return Err(invalid_multisend("missing multiSend(bytes) selector"));
+    };
+
+    if args.len() < 64 {
+        return Err(invalid_multisend("truncated bytes argument"));
+    }
+    if read_len(&args[..32])? != 32 {
+        return Err(invalid_multisend("unexpected bytes offset"));
+    }
+    let len = read_len(&args[32..64])?;
+    let padded_len = len.div_ceil(32).saturating_mul(32);
+    let body = &args[64..];
+    if body.len() < padded_len {
+        return Err(invalid_multisend("truncated bytes argument"));
+    }
+    if body.len() > padded_len {
+        return Err(invalid_multisend("trailing bytes after bytes argument"));
+    }
+    let (mut packed, padding) = body.split_at(len);
+    if padding.iter().any(|b| *b != 0) {
+        return Err(invalid_multisend("nonzero padding"));
+    }
+
+    let mut transactions = Vec::new();
+    while !packed.is_empty() {
+        if packed.len() < PACKED_HEADER_LEN {
+            return Err(invalid_multisend(format!(
+                "truncated entry {}",
+                transactions.len()
+            )));
+        }
+        let (header, rest) = packed.split_at(PACKED_HEADER_LEN);
+        let operation = OperationType::from_u8(header[0]).ok_or_else(|| {
+            invalid_multisend(format!(
+                "unknown operation {} in entry {}",
+                header[0],
+                transactions.len()
+            ))
+        })?;
+        let to = Address::from_slice(&header[1..21]);
+        let value = U256::from_big_endian(&header[21..53]);
+        let data_len = read_len(&header[53..85])?;
+        if rest.len() < data_len {
+            return Err(invalid_multisend(format!(
+                "truncated data in entry {}",
+                transactions.len()
+            )));
+        }
+        let (data, rest) = rest.split_at(data_len);
+
+        transactions.push(SafeTransaction {
+            to,
+            operation,
+            data: format!("0x{}", hex::encode(data)),
+            value: value.to_string(),
+        });
+        packed = rest;
+    }
+
+    Ok(transactions)
+}
+
+#[test]
+fn test_multisend_rejects_invalid_calldata() {
+    let txn = |data: &str, value: &str| SafeTransaction {
+        to: Address::zero(),
+        operation: OperationType::Call,
+        data: data.to_string(),
+        value: value.to_string(),
+    };
+    let multisend = Address::zero();
+
+    assert!(
+        create_safe_multisend_transaction(&[txn("0x", "0"), txn("0x12", "1")], &multisend).is_ok()
+    );
+    assert!(matches!(
+        create_safe_multisend_transaction(&[txn("0x", "0"), txn("0xzz", "0")], &multisend),
+        Err(RelayerClientException::Encoding(_))
+    ));
+    assert!(matches!(
+        create_safe_multisend_transaction(&[txn("0x", "0"), txn("0x", "-1")], &multisend),
+        Err(RelayerClientException::Encoding(_))
+    ));
+    assert!(create_safe_multisend_transaction(&[], &multisend).is_err());
+}
+
+#[test]
+fn test_decode_multisend_round_trips() {
+    use rand::{Rng, SeedableRng};
+
+    let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+    let multisend = Address::repeat_byte(0x42);
+    for _ in 0..200 {
+        let txns: Vec<SafeTransaction> = (0..rng.gen_range(2..6))
+            .map(|_| {
+                let data: Vec<u8> = (0..rng.gen_range(0..100)).map(|_| rng.r#gen()).collect();
+                let value = U256::from(rng.r#gen::<u128>()) * U256::from(rng.gen_range(0..3u8));
+                SafeTransaction {
+                    to: Address::from(rng.r#gen::<[u8; 20]>()),
+                    operation: if rng.gen_bool(0.5) {
+                        OperationType::Call
+                    } else {
+                        OperationType::DelegateCall
+                    },
+                    data: format!("0x{}", hex::encode(data)),
+                    value: value.to_string(),
+                }
+            })
+            .collect();
+
+        let encoded = create_safe_multisend_transaction(&txns, &multisend).unwrap();
+        assert_eq!(decode_multisend(&encoded.data).unwrap(), txns);
+    }
+}
+
+#[test]
+fn test_decode_multisend_rejects_malformed_calldata() {
+    let txn = SafeTransaction {
+        to: Address::repeat_byte(0x11),
+        operation: OperationType::Call,
+        data: "0xdeadbeef".to_string(),
+        value: "1".to_string(),
+    };
+    let encoded = create_safe_multisend_transaction(&[txn.clone(), txn], &Address::zero())
+        .unwrap()
+        .data;
+    let bytes = hex::decode(&encoded[2..]).unwrap();
+    let hex_of = |bytes: &[u8]| format!("0x{}", hex::encode(bytes));
+    let is_invalid = |data: &str| {
+        matches!(
+            decode_multisend(data),
+            Err(RelayerClientException::Encoding(_))
+        )
+    };
+
+    // Wrong selector
+    let mut wrong_selector = bytes.clone();
+    wrong_selector[0] ^= 1;
+    assert!(is_invalid(&hex_of(&wrong_selector)));
+    // Truncated anywhere
+    for len in [3, 40, 70, bytes.len() - 1] {
+        assert!(is_invalid(&hex_of(&bytes[..len])));
     }
+    // Trailing bytes after the padded argument
+    let mut trailing = bytes.clone();
+    trailing.extend_from_slice(&[0; 32]);
+    assert!(is_invalid(&hex_of(&trailing)));
+    // Nonzero padding
+    let mut dirty = bytes.clone();
+    *dirty.last_mut().unwrap() = 1;
+    assert!(is_invalid(&hex_of(&dirty)));
+    // An entry whose dataLen runs past the packed bytes
+    let mut overlong = bytes.clone();
+    overlong[4 + 64 + 84] = 0xff;
+    assert!(is_invalid(&hex_of(&overlong)));
+    // Unknown operation
+    let mut operation = bytes.clone();
+    operation[4 + 64] = 2;
+    assert!(is_invalid(&hex_of(&operation)));
 }
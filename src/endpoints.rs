@@ -3,4 +3,5 @@ pub const GET_TRANSACTION: &str = "/transaction";
 pub const GET_TRANSACTIONS: &str = "/transactions";
 pub const SUBMIT_TRANSACTION: &str = "/submit";
 pub const GET_DEPLOYED: &str = "/deployed";
+pub const GET_RELAY_PAYLOAD: &str = "/relay-payload";
 
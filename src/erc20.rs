@@ -0,0 +1,99 @@
+//! ERC-20 calls with amounts checked against the token's decimals.
+
+use crate::encode::contract_call;
+use crate::errors::{RelayerClientException, TokenAmountError};
+use crate::models::SafeTransaction;
+use crate::token::{Token, TokenAmount};
+use ethabi::Token as AbiToken;
+use ethers::types::Address;
+
+/// `transfer(to, amount)` on `token`.
+pub fn transfer(
+    token: &Token,
+    to: Address,
+    amount: TokenAmount,
+) -> Result<SafeTransaction, RelayerClientException> {
+    call_with_amount(token, "transfer(address,uint256)", to, amount)
+}
+
+/// `approve(spender, amount)` on `token`.
+pub fn approve(
+    token: &Token,
+    spender: Address,
+    amount: TokenAmount,
+) -> Result<SafeTransaction, RelayerClientException> {
+    call_with_amount(token, "approve(address,uint256)", spender, amount)
+}
+
+/// `increaseAllowance(spender, added)` on `token`. Not part of ERC-20, but
+/// supported by USDC and OpenZeppelin tokens.
+pub fn increase_allowance(
+    token: &Token,
+    spender: Address,
+    added: TokenAmount,
+) -> Result<SafeTransaction, RelayerClientException> {
+    call_with_amount(token, "increaseAllowance(address,uint256)", spender, added)
+}
+
+fn call_with_amount(
+    token: &Token,
+    signature: &str,
+    account: Address,
+    amount: TokenAmount,
+) -> Result<SafeTransaction, RelayerClientException> {
+    if amount.decimals() != token.decimals {
+        return Err(TokenAmountError::DecimalsMismatch {
+            expected: token.decimals,
+            found: amount.decimals(),
+        }
+        .into());
+    }
+    Ok(contract_call(
+        token.address,
+        signature,
+        &[AbiToken::Address(account), AbiToken::Uint(amount.raw())],
+    ))
+}
+
+#[test]
+fn test_erc20_calldata() {
+    use ethers::types::U256;
+
+    let usdc = crate::token::token(137, "USDC.e").unwrap();
+    let recipient: Address = "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266"
+        .parse()
+        .unwrap();
+
+    let txn = transfer(&usdc, recipient, usdc.amount("12.5").unwrap()).unwrap();
+    assert_eq!(txn.to, usdc.address);
+    assert_eq!(txn.value, "0");
+    assert_eq!(
+        txn.data,
+        "0xa9059cbb\
+         000000000000000000000000f39fd6e51aad88f6f4ce6ab8827279cfffb92266\
+         0000000000000000000000000000000000000000000000000000000000bebc20"
+    );
+    assert!(
+        approve(&usdc, recipient, usdc.amount("1").unwrap())
+            .unwrap()
+            .data
+            .starts_with("0x095ea7b3")
+    );
+    assert!(
+        increase_allowance(&usdc, recipient, usdc.amount("1").unwrap())
+            .unwrap()
+            .data
+            .starts_with("0x39509351")
+    );
+
+    // An 18-decimal amount is not silently sent as USDC
+    assert!(matches!(
+        transfer(&usdc, recipient, TokenAmount::from_raw(U256::exp10(18), 18)),
+        Err(RelayerClientException::Amount(
+            TokenAmountError::DecimalsMismatch {
+                expected: 6,
+                found: 18
+            }
+        ))
+    ));
+}
@@ -1,14 +1,66 @@
+use alloy::signers::local::LocalSignerError;
+use ethers::types::Address;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub enum RelayerClientException {
-    #[error("{0}")]
-    Message(String),
+    #[error("invalid private key: {0}")]
+    InvalidKey(#[source] LocalSignerError),
+    #[error("unsupported chain id: {0}")]
+    UnsupportedChain(u64),
+    /// A custom [`crate::config::ContractConfig`] that cannot be used.
+    #[error("invalid contract config: {0}")]
+    InvalidConfig(String),
+    #[error("expected safe {0:?} is not deployed")]
+    SafeNotDeployed(Address),
+    #[error("safe {0:?} is already deployed")]
+    SafeAlreadyDeployed(Address),
+    #[error("signer is required for this endpoint")]
+    MissingSigner,
+    #[error("builder credentials are required for this endpoint")]
+    MissingBuilderCredentials,
+    /// The builder authentication headers could not be generated.
+    #[error("builder authentication failed: {0}")]
+    BuilderAuth(String),
+    #[error("failed to fetch nonce: {0}")]
+    NonceFetch(#[source] RelayerApiException),
+    #[error("signing failed: {0}")]
+    Signing(#[source] alloy::signers::Error),
+    /// Invalid input to a request builder, such as malformed hex or an
+    /// amount that is not a uint256.
+    #[error("encoding failed: {0}")]
+    Encoding(String),
+    /// A signature that does not recover to the expected owner.
+    #[error("invalid signature: {0}")]
+    InvalidSignature(String),
+    /// A symbol missing from [`crate::token::tokens`] for the chain.
+    #[error("unknown token {symbol} on chain {chain_id}")]
+    UnknownToken { chain_id: u64, symbol: String },
+    #[error("invalid token amount: {0}")]
+    Amount(#[from] TokenAmountError),
+    #[error("relayer request failed: {0}")]
+    Api(#[from] RelayerApiException),
+    /// The runtime behind the blocking client could not be built.
+    #[error("failed to build runtime: {0}")]
+    Runtime(#[source] std::io::Error),
+    /// A key or signing failure of the ethers-based [`crate::signer::Signer`].
+    #[error("wallet error: {0}")]
+    Wallet(#[source] ethers::signers::WalletError),
 }
 
 impl RelayerClientException {
-    pub fn new(msg: impl Into<String>) -> Self {
-        RelayerClientException::Message(msg.into())
+    pub(crate) fn encoding(msg: impl Into<String>) -> Self {
+        RelayerClientException::Encoding(msg.into())
+    }
+
+    /// Whether the same call may succeed if made again.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            RelayerClientException::NonceFetch(e) | RelayerClientException::Api(e) => {
+                e.is_retryable()
+            }
+            _ => false,
+        }
     }
 }
 
@@ -18,21 +70,122 @@ pub enum RelayerApiException {
     ApiError {
         status_code: Option<u16>,
         error_msg: String,
+        /// The `error` (or `message`) field of a JSON error body.
+        relayer_error: Option<String>,
     },
     #[error("Request exception: {0}")]
     RequestException(String),
+    /// The request never reached the relayer.
+    #[error("Connection failed: {0}")]
+    ConnectionFailed(String),
+    #[error("Request timed out: {0}")]
+    Timeout(String),
+    /// A 2xx response whose body could not be decoded.
+    #[error("Invalid response: {0}")]
+    InvalidResponse(String),
+    /// The submit response carried no transaction ID to look up.
+    #[error("No transaction ID")]
+    MissingTransactionId,
 }
 
 impl RelayerApiException {
     pub fn new(status_code: Option<u16>, error_msg: String) -> Self {
+        let relayer_error = serde_json::from_str::<serde_json::Value>(&error_msg)
+            .ok()
+            .and_then(|body| {
+                ["error", "message"]
+                    .iter()
+                    .find_map(|key| body.get(key)?.as_str().map(str::to_string))
+            });
         RelayerApiException::ApiError {
             status_code,
             error_msg,
+            relayer_error,
         }
     }
 
     pub fn from_request_error(msg: String) -> Self {
         RelayerApiException::RequestException(msg)
     }
+
+    pub fn status_code(&self) -> Option<u16> {
+        match self {
+            RelayerApiException::ApiError { status_code, .. } => *status_code,
+            _ => None,
+        }
+    }
+
+    /// Whether the same request may succeed if made again: rate limiting,
+    /// server errors and network failures.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            RelayerApiException::ApiError { status_code, .. } => {
+                matches!(status_code, Some(429) | Some(500..=599))
+            }
+            RelayerApiException::RequestException(_)
+            | RelayerApiException::ConnectionFailed(_)
+            | RelayerApiException::Timeout(_) => true,
+            RelayerApiException::InvalidResponse(_) | RelayerApiException::MissingTransactionId => {
+                false
+            }
+        }
+    }
 }
 
+/// A [`crate::token::TokenAmount`] that cannot be represented exactly.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum TokenAmountError {
+    #[error("{0:?} is not a decimal amount")]
+    Invalid(String),
+    #[error("{amount:?} has more than {decimals} decimals")]
+    TooPrecise { amount: String, decimals: u8 },
+    #[error("{0:?} does not fit in a uint256")]
+    Overflow(String),
+    #[error("amount has {found} decimals but the token has {expected}")]
+    DecimalsMismatch { expected: u8, found: u8 },
+}
+
+#[test]
+fn test_error_classification() {
+    use std::error::Error;
+
+    let rate_limited = RelayerApiException::new(Some(429), r#"{"error":"slow down"}"#.to_string());
+    assert!(rate_limited.is_retryable());
+    assert_eq!(rate_limited.status_code(), Some(429));
+    assert!(matches!(
+        &rate_limited,
+        RelayerApiException::ApiError { relayer_error: Some(e), .. } if e == "slow down"
+    ));
+
+    let bad_request = RelayerApiException::new(Some(400), "not json".to_string());
+    assert!(!bad_request.is_retryable());
+    assert!(matches!(
+        bad_request,
+        RelayerApiException::ApiError {
+            relayer_error: None,
+            ..
+        }
+    ));
+
+    let nonce = RelayerClientException::NonceFetch(rate_limited);
+    assert!(nonce.is_retryable());
+    assert!(nonce.source().is_some());
+    assert!(!RelayerClientException::SafeNotDeployed(Address::zero()).is_retryable());
+
+    assert!(!RelayerClientException::BuilderAuth("bad secret".to_string()).is_retryable());
+    assert!(!RelayerClientException::UnsupportedChain(1).is_retryable());
+    assert!(
+        !RelayerClientException::UnknownToken {
+            chain_id: 137,
+            symbol: "DAI".to_string(),
+        }
+        .is_retryable()
+    );
+    assert!(!RelayerApiException::MissingTransactionId.is_retryable());
+    assert!(!RelayerClientException::Runtime(std::io::Error::other("no threads")).is_retryable());
+    assert!(matches!(
+        crate::signer::Signer::new("0xnot-a-key", 137),
+        Err(e @ RelayerClientException::Wallet(_)) if !e.is_retryable()
+    ));
+    assert!(!RelayerClientException::Api(RelayerApiException::MissingTransactionId).is_retryable());
+}
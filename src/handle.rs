@@ -0,0 +1,185 @@
+use crate::client::RelayClient;
+use crate::errors::RelayerApiException;
+use crate::models::{RelayerTransaction, RelayerTransactionState};
+use crate::response::ClientRelayerTransactionResponse;
+use crate::watch::{TransactionOutcome, WatchOptions};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// The persistable part of a [`TransactionHandle`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionRef {
+    #[serde(rename = "transactionID")]
+    pub transaction_id: String,
+    pub transaction_hash: Option<String>,
+}
+
+/// A submitted transaction that owns a shared client, so it can be moved
+/// across tasks, queued, or stored as a [`TransactionRef`] and rebuilt with
+/// [`TransactionHandle::new`] after a restart.
+#[derive(Clone)]
+pub struct TransactionHandle {
+    client: Arc<RelayClient>,
+    reference: TransactionRef,
+}
+
+impl TransactionHandle {
+    pub fn new(client: Arc<RelayClient>, reference: TransactionRef) -> Self {
+        TransactionHandle { client, reference }
+    }
+
+    /// Takes over a response returned by `execute`, `deploy` or
+    /// `submit_signed` on `client`.
+    pub fn from_response(
+        client: Arc<RelayClient>,
+        response: &ClientRelayerTransactionResponse<'_>,
+    ) -> Result<Self, RelayerApiException> {
+        let Some(transaction_id) = response.transaction_id.clone() else {
+            return Err(RelayerApiException::MissingTransactionId);
+        };
+        Ok(TransactionHandle::new(
+            client,
+            TransactionRef {
+                transaction_id,
+                transaction_hash: response.transaction_hash.clone(),
+            },
+        ))
+    }
+
+    pub fn reference(&self) -> &TransactionRef {
+        &self.reference
+    }
+
+    pub fn transaction_id(&self) -> &str {
+        &self.reference.transaction_id
+    }
+
+    /// The last known transaction hash; it changes when the relayer
+    /// resubmits, see [`TransactionHandle::refresh`].
+    pub fn transaction_hash(&self) -> Option<&str> {
+        self.reference.transaction_hash.as_deref()
+    }
+
+    pub fn client(&self) -> &Arc<RelayClient> {
+        &self.client
+    }
+
+    /// Fetches the transaction and records its current hash. Returns `None`
+    /// while the relayer does not list it.
+    pub async fn refresh(&mut self) -> Result<Option<RelayerTransaction>, RelayerApiException> {
+        let txn = self
+            .client
+            .get_transaction(&self.reference.transaction_id)
+            .await?
+            .into_iter()
+            .next();
+        if let Some(hash) = txn.as_ref().and_then(|txn| txn.transaction_hash.clone()) {
+            self.reference.transaction_hash = Some(hash);
+        }
+        Ok(txn)
+    }
+
+    /// The current state as reported by the relayer.
+    pub async fn status(&self) -> Result<Option<RelayerTransactionState>, RelayerApiException> {
+        Ok(self
+            .client
+            .get_transaction(&self.reference.transaction_id)
+            .await?
+            .into_iter()
+            .next()
+            .map(|txn| txn.state))
+    }
+
+    /// Waits for the outcome, recording the final hash.
+    pub async fn wait(
+        &mut self,
+        options: WatchOptions,
+    ) -> Result<TransactionOutcome, RelayerApiException> {
+        let outcome = self
+            .client
+            .wait_for_outcome(&self.reference.transaction_id, options)
+            .await?;
+        if let Some(hash) = outcome
+            .transaction()
+            .and_then(|txn| txn.transaction_hash.clone())
+        {
+            self.reference.transaction_hash = Some(hash);
+        }
+        Ok(outcome)
+    }
+}
+
+impl std::fmt::Debug for TransactionHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TransactionHandle")
+            .field("transaction_id", &self.reference.transaction_id)
+            .field("transaction_hash", &self.reference.transaction_hash)
+            .finish()
+    }
+}
+
+#[tokio::test]
+async fn test_transaction_handle_rehydrates_and_waits() {
+    use crate::transport::{RelayTransport, TransportRequest, TransportResponse};
+    use reqwest::header::HeaderMap;
+    use std::time::Duration;
+
+    fn assert_owned<T: Send + Sync + 'static>() {}
+    assert_owned::<TransactionHandle>();
+
+    struct MinedTransport;
+
+    #[async_trait::async_trait]
+    impl RelayTransport for MinedTransport {
+        async fn send(
+            &self,
+            request: TransportRequest,
+        ) -> Result<TransportResponse, RelayerApiException> {
+            assert!(request.url.ends_with("/transaction?id=tx-1"));
+            Ok(TransportResponse {
+                status: 200,
+                headers: HeaderMap::new(),
+                body:
+                    r#"[{"transactionID":"tx-1","state":"STATE_MINED","transactionHash":"0xdef"}]"#
+                        .to_string(),
+            })
+        }
+    }
+
+    let client = Arc::new(
+        RelayClient::new("http://relayer.test", 137, None, None)
+            .unwrap()
+            .with_transport(MinedTransport),
+    );
+
+    // Stored by one process...
+    let stored = serde_json::to_string(&TransactionRef {
+        transaction_id: "tx-1".to_string(),
+        transaction_hash: Some("0xabc".to_string()),
+    })
+    .unwrap();
+    assert_eq!(
+        stored,
+        r#"{"transactionID":"tx-1","transactionHash":"0xabc"}"#
+    );
+
+    // ...and resumed in another task after a restart
+    let mut handle = TransactionHandle::new(client, serde_json::from_str(&stored).unwrap());
+    let outcome = tokio::spawn(async move {
+        let outcome = handle
+            .wait(WatchOptions {
+                poll_interval: Duration::from_millis(1),
+                ..WatchOptions::default()
+            })
+            .await;
+        (outcome, handle)
+    });
+    let (outcome, handle) = outcome.await.unwrap();
+    assert!(matches!(outcome, Ok(TransactionOutcome::Mined(_))));
+    assert_eq!(handle.transaction_hash(), Some("0xdef"));
+    assert_eq!(
+        handle.status().await.unwrap(),
+        Some(RelayerTransactionState::StateMined)
+    );
+}
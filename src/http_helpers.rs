@@ -1,30 +1,97 @@
-use crate::{TransactionRequest, errors::RelayerApiException};
-use reqwest::blocking::Client;
-use serde_json::{Value, json};
-use std::time::Duration;
-
-const TIMEOUT: Duration = Duration::from_secs(30);
+use crate::errors::RelayerApiException;
+use crate::retry::RetryPolicy;
+use crate::transport::{RelayTransport, TransportRequest};
+use reqwest::Method;
+use reqwest::header::{CONTENT_TYPE, HeaderMap, HeaderValue};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use serde_json::ser::Formatter;
+use std::io;
 
 pub enum RequestData {
     Value(Value),
-    TransactionRequest(TransactionRequest),
+    /// A body produced by [`canonical_json`], sent byte for byte.
+    Canonical(String),
+}
+
+/// Serializes `value` into the exact string that is both sent to the relayer
+/// and covered by the builder signature.
+///
+/// The output is byte for byte what Python's `json.dumps` produces with its
+/// default arguments for the same keys in the same order: `", "` and `": "`
+/// separators, and every character outside printable ASCII, DEL included,
+/// escaped as `\uXXXX`.
+pub fn canonical_json<T: Serialize + ?Sized>(value: &T) -> Result<String, serde_json::Error> {
+    let mut out = Vec::new();
+    let mut ser = serde_json::Serializer::with_formatter(&mut out, CanonicalFormatter);
+    value.serialize(&mut ser)?;
+    String::from_utf8(out).map_err(|e| serde_json::Error::io(io::Error::other(e)))
+}
+
+struct CanonicalFormatter;
+
+impl Formatter for CanonicalFormatter {
+    fn begin_array_value<W: ?Sized + io::Write>(
+        &mut self,
+        writer: &mut W,
+        first: bool,
+    ) -> io::Result<()> {
+        if first {
+            Ok(())
+        } else {
+            writer.write_all(b", ")
+        }
+    }
+
+    fn begin_object_key<W: ?Sized + io::Write>(
+        &mut self,
+        writer: &mut W,
+        first: bool,
+    ) -> io::Result<()> {
+        if first {
+            Ok(())
+        } else {
+            writer.write_all(b", ")
+        }
+    }
+
+    fn begin_object_value<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(b": ")
+    }
+
+    fn write_string_fragment<W: ?Sized + io::Write>(
+        &mut self,
+        writer: &mut W,
+        fragment: &str,
+    ) -> io::Result<()> {
+        for c in fragment.chars() {
+            if c.is_ascii() && c != '\x7f' {
+                writer.write_all(&[c as u8])?;
+            } else {
+                let mut units = [0u16; 2];
+                for unit in c.encode_utf16(&mut units) {
+                    write!(writer, "\\u{:04x}", unit)?;
+                }
+            }
+        }
+        Ok(())
+    }
 }
 
-pub fn request(
+pub async fn request<T: DeserializeOwned>(
+    transport: &dyn RelayTransport,
+    retry: &RetryPolicy,
     endpoint: &str,
     method: &str,
-    headers: Option<reqwest::header::HeaderMap>,
+    headers: Option<HeaderMap>,
     data: Option<&RequestData>,
-) -> Result<Value, RelayerApiException> {
-    let client = Client::builder().timeout(TIMEOUT).build().map_err(|e| {
-        RelayerApiException::from_request_error(format!("Failed to create client: {}", e))
-    })?;
-
-    let mut req = match method {
-        "GET" => client.get(endpoint),
-        "POST" => client.post(endpoint),
-        "DELETE" => client.delete(endpoint),
-        "PUT" => client.put(endpoint),
+) -> Result<T, RelayerApiException> {
+    let method = match method {
+        "GET" => Method::GET,
+        "POST" => Method::POST,
+        "DELETE" => Method::DELETE,
+        "PUT" => Method::PUT,
         _ => {
             return Err(RelayerApiException::from_request_error(format!(
                 "Unsupported method: {}",
@@ -33,52 +100,179 @@ pub fn request(
         }
     };
 
-    if let Some(h) = headers {
-        req = req.headers(h);
-    }
-
-    if let Some(d) = data {
-        match d {
-            RequestData::Value(v) => req = req.json(v),
-            RequestData::TransactionRequest(tr) => {
-                req = req.body(reqwest::blocking::Body::from(
-                    serde_json::to_string(tr)
-                        .unwrap()
-                        .replace(":\"", ": \"")
-                        .replace(":{", ": {")
-                        .replace(",\"", ", \""),
-                ));
-            }
+    let mut headers = headers.unwrap_or_default();
+    let body = match data {
+        Some(RequestData::Value(v)) => {
+            headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+            Some(serde_json::to_string(v).map_err(|e| {
+                RelayerApiException::from_request_error(format!("Failed to encode body: {}", e))
+            })?)
         }
-    }
+        Some(RequestData::Canonical(body)) => {
+            headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+            Some(body.clone())
+        }
+        None => None,
+    };
 
-    let resp = req
-        .send()
-        .map_err(|e| RelayerApiException::from_request_error(format!("Request failed: {}", e)))?;
+    let idempotent = method == Method::GET;
+    let mut attempt = 1;
+    let resp = loop {
+        let result = transport
+            .send(TransportRequest {
+                method: method.clone(),
+                url: endpoint.to_string(),
+                headers: headers.clone(),
+                body: body.clone(),
+            })
+            .await;
+        let retryable = attempt < retry.max_attempts
+            && match &result {
+                Ok(resp) => retry.should_retry_status(resp.status, idempotent),
+                Err(e) => retry.should_retry_error(e, idempotent),
+            };
+        if !retryable {
+            break result?;
+        }
+        tokio::time::sleep(retry.delay(attempt, result.as_ref().ok())).await;
+        attempt += 1;
+    };
 
-    let status = resp.status();
-    if !status.is_success() {
-        let error_msg = resp.text().unwrap_or_else(|_| "Unknown error".to_string());
-        return Err(RelayerApiException::new(Some(status.as_u16()), error_msg));
+    if !(200..300).contains(&resp.status) {
+        let error_msg = if resp.body.is_empty() {
+            "Unknown error".to_string()
+        } else {
+            resp.body
+        };
+        return Err(RelayerApiException::new(Some(resp.status), error_msg));
     }
 
-    resp.json::<Value>().map_err(|e| {
-        RelayerApiException::from_request_error(format!("Failed to parse JSON: {}", e))
-    })
+    serde_json::from_str::<T>(&resp.body)
+        .map_err(|e| RelayerApiException::InvalidResponse(format!("Failed to parse JSON: {}", e)))
 }
 
-pub fn post(
+pub async fn post<T: DeserializeOwned>(
+    transport: &dyn RelayTransport,
+    retry: &RetryPolicy,
     endpoint: &str,
-    headers: Option<reqwest::header::HeaderMap>,
+    headers: Option<HeaderMap>,
     data: Option<&RequestData>,
-) -> Result<Value, RelayerApiException> {
-    request(endpoint, "POST", headers, data)
+) -> Result<T, RelayerApiException> {
+    request(transport, retry, endpoint, "POST", headers, data).await
 }
 
-pub fn get(
+pub async fn get<T: DeserializeOwned>(
+    transport: &dyn RelayTransport,
+    retry: &RetryPolicy,
     endpoint: &str,
-    headers: Option<reqwest::header::HeaderMap>,
+    headers: Option<HeaderMap>,
     data: Option<&RequestData>,
-) -> Result<Value, RelayerApiException> {
-    request(endpoint, "GET", headers, data)
+) -> Result<T, RelayerApiException> {
+    request(transport, retry, endpoint, "GET", headers, data).await
+}
+
+#[test]
+fn test_canonical_json_layout() {
+    let value = serde_json::json!({"a": [1, 2], "b": {"c": "d"}});
+    assert_eq!(
+        canonical_json(&value).unwrap(),
+        r#"{"a": [1, 2], "b": {"c": "d"}}"#
+    );
+
+    // Printed by CPython 3.11.7 for
+    // `json.dumps({"a": [1, 2], "c": "back\\slash\ttab\nnl\x1f\x7f/", "m": "it's \"café\" 🚀"})`
+    let value = serde_json::json!({
+        "a": [1, 2],
+        "c": "back\\slash\ttab\nnl\u{1f}\u{7f}/",
+        "m": "it's \"café\" 🚀",
+    });
+    assert_eq!(
+        canonical_json(&value).unwrap(),
+        r#"{"a": [1, 2], "c": "back\\slash\ttab\nnl\u001f\u007f/", "m": "it's \"caf\u00e9\" \ud83d\ude80"}"#
+    );
+}
+
+#[tokio::test]
+async fn test_canonical_body_with_adversarial_metadata() {
+    use crate::models::{SignatureParams, TransactionRequest};
+    use crate::transport::{RelayTransport, TransportRequest, TransportResponse};
+    use std::sync::Mutex;
+
+    struct RecordingTransport {
+        body: Mutex<Option<String>>,
+    }
+
+    #[async_trait::async_trait]
+    impl RelayTransport for RecordingTransport {
+        async fn send(
+            &self,
+            request: TransportRequest,
+        ) -> Result<TransportResponse, RelayerApiException> {
+            *self.body.lock().unwrap() = request.body;
+            Ok(TransportResponse {
+                status: 200,
+                headers: HeaderMap::new(),
+                body: "{}".to_string(),
+            })
+        }
+    }
+
+    let transport = RecordingTransport {
+        body: Mutex::new(None),
+    };
+    for metadata in [
+        r#"it's a "quoted" value"#,
+        "key: value, other:{nested}",
+        r#"{"looks":"like json"}"#,
+        "back\\slash\ttab\nnewline",
+        "ünïcödé 日本語 🚀",
+        "'",
+    ] {
+        let request = TransactionRequest {
+            transaction_type: "SAFE".to_string(),
+            from_address: "0x0000000000000000000000000000000000000001".to_string(),
+            to: "0x0000000000000000000000000000000000000002".to_string(),
+            proxy: "0x0000000000000000000000000000000000000003".to_string(),
+            data: "0x".to_string(),
+            signature: "0x00".to_string(),
+            value: None,
+            signature_params: SignatureParams {
+                gas_price: Some("0".to_string()),
+                operation: None,
+                safe_txn_gas: None,
+                base_gas: None,
+                gas_token: None,
+                refund_receiver: None,
+                payment_token: None,
+                payment: None,
+                payment_receiver: None,
+                gas_limit: None,
+                relayer_fee: None,
+                relay_hub: None,
+                relay: None,
+            },
+            nonce: Some("0".to_string()),
+            metadata: Some(metadata.to_string()),
+        };
+
+        let body = canonical_json(&request).unwrap();
+        assert!(body.is_ascii());
+        let restored: TransactionRequest = serde_json::from_str(&body).unwrap();
+        assert_eq!(restored.metadata.as_deref(), Some(metadata));
+        assert_eq!(canonical_json(&restored).unwrap(), body);
+
+        let _: Value = post(
+            &transport,
+            &RetryPolicy::none(),
+            "http://relayer.test/submit",
+            None,
+            Some(&RequestData::Canonical(body.clone())),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            transport.body.lock().unwrap().as_deref(),
+            Some(body.as_str())
+        );
+    }
 }
@@ -1,3 +1,5 @@
+#[cfg(feature = "blocking")]
+pub mod blocking;
 pub mod builder;
 pub mod client;
 pub mod config;
@@ -5,18 +7,35 @@ pub mod constants;
 pub mod conversion;
 pub mod encode;
 pub mod endpoints;
+pub mod erc20;
 pub mod errors;
+pub mod handle;
 pub mod http_helpers;
+#[cfg(feature = "test-utils")]
+pub mod mock;
 pub mod model;
 pub mod models;
+pub mod nonce;
+pub mod polymarket;
 pub mod response;
+pub mod retry;
 pub mod signer;
+pub mod token;
+pub mod transport;
 pub mod utils;
+pub mod watch;
 
 pub use client::RelayClient;
-pub use errors::{RelayerApiException, RelayerClientException};
+pub use config::ContractConfig;
+pub use errors::{RelayerApiException, RelayerClientException, TokenAmountError};
+pub use handle::{TransactionHandle, TransactionRef};
 pub use models::{
-    OperationType, RelayerTransactionState, SafeTransaction, SignatureParams, TransactionRequest,
-    TransactionType,
+    CallType, NonceResponse, OperationType, ProxyTransaction, RelayPayload, RelayerTransaction,
+    RelayerTransactionState, SafeGasParams, SafeTransaction, SignatureParams,
+    SubmitTransactionResponse, TransactionRequest, TransactionType,
 };
 pub use response::ClientRelayerTransactionResponse;
+pub use retry::RetryPolicy;
+pub use token::{Token, TokenAmount};
+pub use transport::{RelayTransport, ReqwestTransport, TransportRequest, TransportResponse};
+pub use watch::{TransactionOutcome, WatchEvent, WatchOptions};
@@ -0,0 +1,574 @@
+//! In-process mock relayer for integration tests, enabled by the
+//! `test-utils` feature.
+//!
+//! [`MockRelayer`] serves `/nonce`, `/relay-payload`, `/deployed`, `/submit`,
+//! `/transaction` and `/transactions` on a random localhost port.
+//! Submissions must carry builder headers signed with the relayer's
+//! [`MockCredentials`] over the exact body sent. Safe transactions must be
+//! signed by their owner for the owner's Safe at exactly the next nonce, and
+//! the Safe must be deployed; deployments must be signed by the owner for the
+//! configured factory. Proxy transactions must be signed by their owner for
+//! the owner's proxy wallet, through [`MOCK_RELAY`] at exactly the nonce
+//! `/relay-payload` returns.
+//! Every `/transaction` poll advances an accepted transaction by one state:
+//! `STATE_NEW` → `STATE_EXECUTED` → `STATE_MINED` → `STATE_CONFIRMED`, unless
+//! a failure was injected with [`MockRelayer::fail_next`].
+
+use crate::builder::{verify_proxy_signature, verify_safe_create_signature, verify_safe_signature};
+use crate::config::{ContractConfig, get_contract_config};
+use crate::endpoints::{
+    GET_DEPLOYED, GET_NONCE, GET_RELAY_PAYLOAD, GET_TRANSACTION, GET_TRANSACTIONS,
+    SUBMIT_TRANSACTION,
+};
+use crate::models::{
+    RelayerTransaction, RelayerTransactionState, TransactionRequest, TransactionType,
+};
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE;
+use chrono::Utc;
+use ethers::types::{Address, H160};
+use ethers::utils::keccak256;
+use hmac::{Hmac, Mac};
+use http_body_util::{BodyExt, Full};
+use hyper::body::{Bytes, Incoming};
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{HeaderMap, Method, Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use serde_json::json;
+use sha2::Sha256;
+use std::collections::{HashMap, HashSet};
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+
+/// Headers a submission must carry, as set by the builder signing.
+pub const BUILDER_HEADERS: [&str; 4] = [
+    "POLY_BUILDER_API_KEY",
+    "POLY_BUILDER_PASSPHRASE",
+    "POLY_BUILDER_SIGNATURE",
+    "POLY_BUILDER_TIMESTAMP",
+];
+
+/// How far `POLY_BUILDER_TIMESTAMP` may be from the relayer's clock.
+pub const MAX_TIMESTAMP_SKEW_SECS: i64 = 30;
+
+/// The relay `/relay-payload` hands out, which PROXY requests must name.
+pub const MOCK_RELAY: Address = H160([0x5e; 20]);
+
+/// The builder API credentials the mock relayer accepts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MockCredentials {
+    pub api_key: String,
+    /// URL-safe base64, as issued by Polymarket.
+    pub secret: String,
+    pub passphrase: String,
+}
+
+impl MockCredentials {
+    pub fn new(
+        api_key: impl Into<String>,
+        secret: impl Into<String>,
+        passphrase: impl Into<String>,
+    ) -> Self {
+        MockCredentials {
+            api_key: api_key.into(),
+            secret: secret.into(),
+            passphrase: passphrase.into(),
+        }
+    }
+
+    /// The URL-safe base64 HMAC-SHA256, keyed with the decoded secret, of
+    /// `timestamp + method + path + body`. `None` if the secret is not
+    /// base64.
+    pub fn signature(
+        &self,
+        timestamp: i64,
+        method: &str,
+        path: &str,
+        body: &[u8],
+    ) -> Option<String> {
+        let secret = URL_SAFE.decode(&self.secret).ok()?;
+        let mut mac = Hmac::<Sha256>::new_from_slice(&secret).ok()?;
+        mac.update(format!("{}{}{}", timestamp, method, path).as_bytes());
+        mac.update(body);
+        Some(URL_SAFE.encode(mac.finalize().into_bytes()))
+    }
+
+    /// The [`BUILDER_HEADERS`] for a request, e.g. to send a hand-made one.
+    pub fn headers(
+        &self,
+        timestamp: i64,
+        method: &str,
+        path: &str,
+        body: &[u8],
+    ) -> Vec<(&'static str, String)> {
+        vec![
+            ("POLY_BUILDER_API_KEY", self.api_key.clone()),
+            ("POLY_BUILDER_PASSPHRASE", self.passphrase.clone()),
+            (
+                "POLY_BUILDER_SIGNATURE",
+                self.signature(timestamp, method, path, body)
+                    .unwrap_or_default(),
+            ),
+            ("POLY_BUILDER_TIMESTAMP", timestamp.to_string()),
+        ]
+    }
+
+    /// Checks the builder headers of a request against these credentials.
+    fn authenticate(
+        &self,
+        headers: &HeaderMap,
+        method: &str,
+        path: &str,
+        body: &[u8],
+    ) -> Result<(), String> {
+        let [api_key, passphrase, signature, timestamp] = BUILDER_HEADERS.map(|name| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .ok_or_else(|| format!("missing {} header", name))
+        });
+        if api_key? != self.api_key || passphrase? != self.passphrase {
+            return Err("invalid builder credentials".to_string());
+        }
+        let timestamp = timestamp?
+            .parse::<i64>()
+            .map_err(|_| "invalid timestamp".to_string())?;
+        if (Utc::now().timestamp() - timestamp).abs() > MAX_TIMESTAMP_SKEW_SECS {
+            return Err("stale timestamp".to_string());
+        }
+        if Some(signature?) != self.signature(timestamp, method, path, body).as_deref() {
+            return Err("invalid builder signature".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// A failure injected into the next accepted submission.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MockFailure {
+    /// Goes `STATE_NEW` → `STATE_EXECUTED` → `STATE_FAILED`.
+    Failed,
+    /// Goes `STATE_NEW` → `STATE_INVALID`.
+    Invalid,
+    /// Rejects the submission with this status and error message.
+    Reject(u16, String),
+    /// Accepts the submission but holds the response back this long, as a
+    /// relayer that is slow to answer.
+    Stall(Duration),
+}
+
+#[derive(Debug, Default)]
+struct MockState {
+    /// Next Safe nonce of each owner.
+    nonces: HashMap<Address, u64>,
+    /// Next relay hub nonce of each owner.
+    proxy_nonces: HashMap<Address, u64>,
+    deployed: HashSet<Address>,
+    transactions: Vec<MockTransaction>,
+    failures: Vec<MockFailure>,
+}
+
+/// Takes `nonce` if it is exactly the next one in `nonces`, or explains why
+/// it is refused.
+fn use_nonce(nonces: &mut HashMap<Address, u64>, owner: Address, nonce: u64) -> Result<(), String> {
+    let next = nonces.entry(owner).or_default();
+    if nonce < *next {
+        return Err(format!("invalid nonce: {} already used", nonce));
+    }
+    if nonce > *next {
+        return Err(format!("invalid nonce: expected {}, got {}", next, nonce));
+    }
+    *next += 1;
+    Ok(())
+}
+
+#[derive(Debug)]
+struct MockTransaction {
+    transaction: RelayerTransaction,
+    request: TransactionRequest,
+    failure: Option<MockFailure>,
+}
+
+impl MockTransaction {
+    fn advance(&mut self, contracts: &ContractConfig, deployed: &mut HashSet<Address>) {
+        use RelayerTransactionState::*;
+        let next = match (&self.transaction.state, &self.failure) {
+            (StateNew, Some(MockFailure::Invalid)) => StateInvalid,
+            (StateNew, _) => StateExecuted,
+            (StateExecuted, Some(MockFailure::Failed)) => StateFailed,
+            (StateExecuted, _) => StateMined,
+            (StateMined, _) => StateConfirmed,
+            _ => return,
+        };
+        if next == StateMined
+            && self.request.transaction_type == TransactionType::SafeCreate.as_str()
+            && let Ok(owner) = Address::from_str(&self.request.from_address)
+            && let Ok(safe) = contracts.safe_address(&owner)
+        {
+            deployed.insert(safe);
+        }
+        self.transaction.state = next;
+        self.transaction.updated_at = Some(Utc::now());
+    }
+}
+
+/// A mock relayer listening on localhost until dropped.
+pub struct MockRelayer {
+    addr: SocketAddr,
+    credentials: MockCredentials,
+    state: Arc<Mutex<MockState>>,
+    task: JoinHandle<()>,
+}
+
+impl MockRelayer {
+    /// Starts a relayer for `chain_id`, which must have a built-in contract
+    /// configuration, accepting submissions signed with `credentials`. A
+    /// client built with a loaded config needs
+    /// [`MockRelayer::start_with_contract_config`] and the same config.
+    pub async fn start(chain_id: u64, credentials: MockCredentials) -> std::io::Result<Self> {
+        let contracts = get_contract_config(chain_id).map_err(std::io::Error::other)?;
+        Self::start_with_contract_config(chain_id, contracts, credentials).await
+    }
+
+    /// Starts a relayer for `chain_id` deriving Safes from `contracts`.
+    pub async fn start_with_contract_config(
+        chain_id: u64,
+        contracts: ContractConfig,
+        credentials: MockCredentials,
+    ) -> std::io::Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+        let state = Arc::new(Mutex::new(MockState::default()));
+
+        let server_state = state.clone();
+        let server_credentials = credentials.clone();
+        let task = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let state = server_state.clone();
+                let contracts = contracts.clone();
+                let credentials = server_credentials.clone();
+                tokio::spawn(async move {
+                    let service = service_fn(move |request| {
+                        handle(
+                            state.clone(),
+                            contracts.clone(),
+                            credentials.clone(),
+                            chain_id,
+                            request,
+                        )
+                    });
+                    // A client closing the connection early is not an error here
+                    let _ = http1::Builder::new()
+                        .serve_connection(TokioIo::new(stream), service)
+                        .await;
+                });
+            }
+        });
+
+        Ok(MockRelayer {
+            addr,
+            credentials,
+            state,
+            task,
+        })
+    }
+
+    /// Base URL to pass to [`crate::RelayClient::new`].
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    pub fn credentials(&self) -> &MockCredentials {
+        &self.credentials
+    }
+
+    fn state(&self) -> MutexGuard<'_, MockState> {
+        lock(&self.state)
+    }
+
+    /// Marks `safe` as deployed without a SAFE-CREATE submission.
+    pub fn set_deployed(&self, safe: Address) {
+        self.state().deployed.insert(safe);
+    }
+
+    /// Sets the Safe nonce of `owner`.
+    pub fn set_nonce(&self, owner: Address, nonce: u64) {
+        self.state().nonces.insert(owner, nonce);
+    }
+
+    /// Applies `failure` to the next submission; several calls queue up.
+    pub fn fail_next(&self, failure: MockFailure) {
+        self.state().failures.push(failure);
+    }
+
+    /// All accepted submissions, oldest first.
+    pub fn submissions(&self) -> Vec<TransactionRequest> {
+        self.state()
+            .transactions
+            .iter()
+            .map(|txn| txn.request.clone())
+            .collect()
+    }
+}
+
+impl Drop for MockRelayer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+fn lock(state: &Mutex<MockState>) -> MutexGuard<'_, MockState> {
+    state.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn respond(status: StatusCode, body: serde_json::Value) -> Response<Full<Bytes>> {
+    let mut response = Response::new(Full::new(Bytes::from(body.to_string())));
+    *response.status_mut() = status;
+    response
+}
+
+fn reject(status: StatusCode, error: impl Into<String>) -> Response<Full<Bytes>> {
+    respond(status, json!({ "error": error.into() }))
+}
+
+async fn handle(
+    state: Arc<Mutex<MockState>>,
+    contracts: ContractConfig,
+    credentials: MockCredentials,
+    chain_id: u64,
+    request: Request<Incoming>,
+) -> Result<Response<Full<Bytes>>, Infallible> {
+    let query: HashMap<String, String> = request
+        .uri()
+        .query()
+        .map(|q| {
+            url::form_urlencoded::parse(q.as_bytes())
+                .into_owned()
+                .collect()
+        })
+        .unwrap_or_default();
+    let address = || query.get("address").and_then(|a| Address::from_str(a).ok());
+
+    let path = request.uri().path().to_string();
+    let response = match (request.method().clone(), path.as_str()) {
+        (Method::GET, GET_NONCE) => match address() {
+            Some(owner) => {
+                let nonce = lock(&state).nonces.get(&owner).copied().unwrap_or(0);
+                respond(StatusCode::OK, json!({ "nonce": nonce.to_string() }))
+            }
+            None => reject(StatusCode::BAD_REQUEST, "invalid address"),
+        },
+        (Method::GET, GET_RELAY_PAYLOAD) => match address() {
+            Some(owner) => {
+                let nonce = lock(&state).proxy_nonces.get(&owner).copied().unwrap_or(0);
+                respond(
+                    StatusCode::OK,
+                    json!({ "address": format!("{:?}", MOCK_RELAY), "nonce": nonce.to_string() }),
+                )
+            }
+            None => reject(StatusCode::BAD_REQUEST, "invalid address"),
+        },
+        (Method::GET, GET_DEPLOYED) => match address() {
+            Some(safe) => {
+                let deployed = lock(&state).deployed.contains(&safe);
+                respond(StatusCode::OK, json!({ "deployed": deployed }))
+            }
+            None => reject(StatusCode::BAD_REQUEST, "invalid address"),
+        },
+        (Method::GET, GET_TRANSACTION) => {
+            let id = query.get("id").cloned().unwrap_or_default();
+            let mut state = lock(&state);
+            let MockState {
+                transactions,
+                deployed,
+                ..
+            } = &mut *state;
+            let found: Vec<_> = transactions
+                .iter_mut()
+                .filter(|txn| txn.transaction.transaction_id == id)
+                .map(|txn| {
+                    let current = txn.transaction.clone();
+                    txn.advance(&contracts, deployed);
+                    current
+                })
+                .collect();
+            respond(StatusCode::OK, json!(found))
+        }
+        (Method::GET, GET_TRANSACTIONS) => {
+            let all: Vec<_> = lock(&state)
+                .transactions
+                .iter()
+                .map(|txn| txn.transaction.clone())
+                .collect();
+            respond(StatusCode::OK, json!(all))
+        }
+        (Method::POST, SUBMIT_TRANSACTION) => {
+            let headers = request.headers().clone();
+            match request.into_body().collect().await {
+                Ok(body) => {
+                    let body = body.to_bytes();
+                    match credentials.authenticate(&headers, "POST", &path, &body) {
+                        Err(error) => reject(StatusCode::UNAUTHORIZED, error),
+                        Ok(()) => {
+                            let (response, stall) = submit(&state, &contracts, chain_id, &body);
+                            if let Some(stall) = stall {
+                                tokio::time::sleep(stall).await;
+                            }
+                            response
+                        }
+                    }
+                }
+                Err(e) => reject(StatusCode::BAD_REQUEST, e.to_string()),
+            }
+        }
+        _ => reject(StatusCode::NOT_FOUND, "not found"),
+    };
+    Ok(response)
+}
+
+fn submit(
+    state: &Mutex<MockState>,
+    contracts: &ContractConfig,
+    chain_id: u64,
+    body: &[u8],
+) -> (Response<Full<Bytes>>, Option<Duration>) {
+    let request: TransactionRequest = match serde_json::from_slice(body) {
+        Ok(request) => request,
+        Err(e) => {
+            return (
+                reject(StatusCode::BAD_REQUEST, format!("invalid body: {}", e)),
+                None,
+            );
+        }
+    };
+    let Ok(owner) = Address::from_str(&request.from_address) else {
+        return (
+            reject(StatusCode::BAD_REQUEST, "invalid from address"),
+            None,
+        );
+    };
+    let is_proxy = request.transaction_type == TransactionType::Proxy.as_str();
+    let wallet = if is_proxy {
+        contracts.proxy_wallet_address(&owner)
+    } else {
+        contracts.safe_address(&owner)
+    };
+    let Ok(wallet) = wallet else {
+        return (
+            reject(StatusCode::BAD_REQUEST, "invalid from address"),
+            None,
+        );
+    };
+    if Address::from_str(&request.proxy).ok() != Some(wallet) {
+        return (
+            reject(
+                StatusCode::BAD_REQUEST,
+                "proxyWallet is not the owner's wallet",
+            ),
+            None,
+        );
+    }
+
+    let mut state = lock(state);
+    if is_proxy {
+        if let Err(e) = verify_proxy_signature(&request, &owner, contracts) {
+            return (reject(StatusCode::BAD_REQUEST, e.to_string()), None);
+        }
+        let relay = request.signature_params.relay.as_deref();
+        if relay.and_then(|r| Address::from_str(r).ok()) != Some(MOCK_RELAY) {
+            return (reject(StatusCode::BAD_REQUEST, "unknown relay"), None);
+        }
+    } else if request.transaction_type == TransactionType::Safe.as_str() {
+        if !state.deployed.contains(&wallet) {
+            return (reject(StatusCode::BAD_REQUEST, "safe not deployed"), None);
+        }
+        if let Err(e) = verify_safe_signature(&request, &owner, chain_id) {
+            return (reject(StatusCode::BAD_REQUEST, e.to_string()), None);
+        }
+    } else if request.transaction_type == TransactionType::SafeCreate.as_str() {
+        if state.deployed.contains(&wallet) {
+            return (
+                reject(StatusCode::BAD_REQUEST, "safe already deployed"),
+                None,
+            );
+        }
+        if let Err(e) = verify_safe_create_signature(&request, &owner, contracts, chain_id) {
+            return (reject(StatusCode::BAD_REQUEST, e.to_string()), None);
+        }
+    } else {
+        return (
+            reject(
+                StatusCode::BAD_REQUEST,
+                format!("unsupported type {}", request.transaction_type),
+            ),
+            None,
+        );
+    }
+
+    if let Some(MockFailure::Reject(status, error)) = state.failures.first() {
+        let response = reject(
+            StatusCode::from_u16(*status).unwrap_or(StatusCode::BAD_REQUEST),
+            error.clone(),
+        );
+        state.failures.remove(0);
+        return (response, None);
+    }
+    if request.transaction_type != TransactionType::SafeCreate.as_str() {
+        let Some(nonce) = request.nonce.as_deref().and_then(|n| n.parse::<u64>().ok()) else {
+            return (reject(StatusCode::BAD_REQUEST, "invalid nonce"), None);
+        };
+        let nonces = if is_proxy {
+            &mut state.proxy_nonces
+        } else {
+            &mut state.nonces
+        };
+        if let Err(error) = use_nonce(nonces, owner, nonce) {
+            return (reject(StatusCode::BAD_REQUEST, error), None);
+        }
+    }
+    let (failure, stall) = match (!state.failures.is_empty()).then(|| state.failures.remove(0)) {
+        Some(MockFailure::Stall(stall)) => (None, Some(stall)),
+        failure => (failure, None),
+    };
+
+    let transaction_id = format!("mock-{}", state.transactions.len() + 1);
+    let transaction_hash = format!("0x{}", hex::encode(keccak256(transaction_id.as_bytes())));
+    let now = Utc::now();
+    let transaction = RelayerTransaction {
+        transaction_id: transaction_id.clone(),
+        state: RelayerTransactionState::StateNew,
+        transaction_hash: Some(transaction_hash.clone()),
+        from: Some(request.from_address.clone()),
+        to: Some(request.to.clone()),
+        proxy_address: Some(request.proxy.clone()),
+        data: Some(request.data.clone()),
+        nonce: request.nonce.clone(),
+        value: request.value.clone(),
+        signature: Some(request.signature.clone()),
+        created_at: Some(now),
+        updated_at: Some(now),
+        metadata: request.metadata.clone(),
+        transaction_type: Some(request.transaction_type.clone()),
+    };
+    state.transactions.push(MockTransaction {
+        transaction,
+        request,
+        failure,
+    });
+
+    let response = respond(
+        StatusCode::OK,
+        json!({
+            "transactionID": transaction_id,
+            "transactionHash": transaction_hash,
+            "state": RelayerTransactionState::StateNew.as_str(),
+        }),
+    );
+    (response, stall)
+}
@@ -1,21 +1,10 @@
-use crate::model::base::BaseEIP712Model;
-use ethers::types::{Address, U256};
-use ethabi::{encode, Token};
+use alloy::core::sol;
 
-pub struct CreateProxy {
-    pub payment_token: Address,
-    pub payment: U256,
-    pub payment_receiver: Address,
-}
-
-impl BaseEIP712Model for CreateProxy {
-    fn signable_bytes(&self, _domain: &[u8]) -> Vec<u8> {
-        let tokens = vec![
-            Token::Address(self.payment_token),
-            Token::Uint(self.payment),
-            Token::Address(self.payment_receiver),
-        ];
-        encode(&tokens)
+sol! {
+    #[derive(Debug)]
+    struct CreateProxy {
+        address paymentToken;
+        uint256 payment;
+        address paymentReceiver;
     }
 }
-
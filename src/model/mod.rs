@@ -1,8 +1,5 @@
-pub mod base;
 pub mod create_proxy;
 pub mod safe_tx;
 
-pub use base::BaseEIP712Model;
 pub use create_proxy::CreateProxy;
 pub use safe_tx::SafeTx;
-
@@ -1,3 +1,5 @@
+use crate::errors::RelayerClientException;
+use chrono::{DateTime, Utc};
 use ethers::types::{Address, U256};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -12,9 +14,17 @@ impl OperationType {
     pub fn as_u8(self) -> u8 {
         self as u8
     }
+
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(OperationType::Call),
+            1 => Some(OperationType::DelegateCall),
+            _ => None,
+        }
+    }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct SafeTransaction {
     pub to: Address,
     pub operation: OperationType,
@@ -22,10 +32,48 @@ pub struct SafeTransaction {
     pub value: String,
 }
 
+/// Call type of a proxy wallet call, as understood by `ProxyWalletFactory`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CallType {
+    Invalid = 0,
+    Call = 1,
+    DelegateCall = 2,
+}
+
+impl CallType {
+    pub fn as_u8(self) -> u8 {
+        self as u8
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ProxyTransaction {
+    pub to: Address,
+    pub type_code: CallType,
+    pub data: String,
+    pub value: String,
+}
+
+impl From<SafeTransaction> for ProxyTransaction {
+    fn from(txn: SafeTransaction) -> Self {
+        let type_code = match txn.operation {
+            OperationType::Call => CallType::Call,
+            OperationType::DelegateCall => CallType::DelegateCall,
+        };
+        ProxyTransaction {
+            to: txn.to,
+            type_code,
+            data: txn.data,
+            value: txn.value,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TransactionType {
     Safe,
     SafeCreate,
+    Proxy,
 }
 
 impl TransactionType {
@@ -33,6 +81,7 @@ impl TransactionType {
         match self {
             TransactionType::Safe => "SAFE",
             TransactionType::SafeCreate => "SAFE-CREATE",
+            TransactionType::Proxy => "PROXY",
         }
     }
 }
@@ -57,6 +106,14 @@ pub struct SignatureParams {
     pub payment: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none", rename = "paymentReceiver")]
     pub payment_receiver: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", rename = "gasLimit")]
+    pub gas_limit: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", rename = "relayerFee")]
+    pub relayer_fee: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", rename = "relayHub")]
+    pub relay_hub: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub relay: Option<String>,
 }
 
 impl SignatureParams {
@@ -101,11 +158,23 @@ impl SignatureParams {
                 Value::String(payment_receiver.clone()),
             );
         }
+        if let Some(ref gas_limit) = self.gas_limit {
+            map.insert("gasLimit".to_string(), Value::String(gas_limit.clone()));
+        }
+        if let Some(ref relayer_fee) = self.relayer_fee {
+            map.insert("relayerFee".to_string(), Value::String(relayer_fee.clone()));
+        }
+        if let Some(ref relay_hub) = self.relay_hub {
+            map.insert("relayHub".to_string(), Value::String(relay_hub.clone()));
+        }
+        if let Some(ref relay) = self.relay {
+            map.insert("relay".to_string(), Value::String(relay.clone()));
+        }
         Value::Object(map)
     }
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TransactionRequest {
     #[serde(rename = "type")]
     pub transaction_type: String,
@@ -126,12 +195,81 @@ pub struct TransactionRequest {
     pub metadata: Option<String>,
 }
 
+/// Gas refund parameters of a `SafeTx`.
+///
+/// With a nonzero `gas_price` the Safe pays `(gasUsed + base_gas) * gas_price`
+/// of `gas_token` (the native token when zero) to `refund_receiver` (the
+/// relayer's `tx.origin` when zero). The default refunds nothing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SafeGasParams {
+    pub safe_tx_gas: String,
+    pub base_gas: String,
+    pub gas_price: String,
+    pub gas_token: Address,
+    pub refund_receiver: Address,
+}
+
+impl Default for SafeGasParams {
+    fn default() -> Self {
+        SafeGasParams {
+            safe_tx_gas: "0".to_string(),
+            base_gas: "0".to_string(),
+            gas_price: "0".to_string(),
+            gas_token: Address::zero(),
+            refund_receiver: Address::zero(),
+        }
+    }
+}
+
+impl SafeGasParams {
+    /// Checks that the amounts are uint256 values and that the combination
+    /// makes sense to the Safe contract.
+    pub fn validate(&self) -> Result<(), RelayerClientException> {
+        let parse = |value: &str, name: &str| {
+            U256::from_dec_str(value)
+                .map_err(|e| RelayerClientException::encoding(format!("Invalid {}: {}", name, e)))
+        };
+        let safe_tx_gas = parse(&self.safe_tx_gas, "safe_tx_gas")?;
+        let base_gas = parse(&self.base_gas, "base_gas")?;
+        let gas_price = parse(&self.gas_price, "gas_price")?;
+
+        if gas_price.is_zero() {
+            if !base_gas.is_zero() || !self.gas_token.is_zero() || !self.refund_receiver.is_zero() {
+                return Err(RelayerClientException::encoding(
+                    "base_gas, gas_token and refund_receiver have no effect without a gas_price",
+                ));
+            }
+        } else if safe_tx_gas.is_zero() {
+            // The Safe would run the inner call with no gas and still pay the refund
+            return Err(RelayerClientException::encoding(
+                "safe_tx_gas must be set when gas_price is nonzero",
+            ));
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct SafeTransactionArgs {
     pub from_address: Address,
     pub nonce: String,
     pub chain_id: u64,
     pub transactions: Vec<SafeTransaction>,
+    /// Refund parameters; `None` refunds nothing.
+    pub gas_params: Option<SafeGasParams>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ProxyTransactionArgs {
+    pub from_address: Address,
+    pub nonce: String,
+    pub gas_price: String,
+    /// Gas limit signed over; `DEFAULT_PROXY_GAS_LIMIT` when `None`.
+    pub gas_limit: Option<String>,
+    /// `proxy(...)` calldata for the proxy wallet factory.
+    pub data: String,
+    /// Relay address returned by `/relay-payload`.
+    pub relay: Address,
 }
 
 #[derive(Debug, Clone)]
@@ -143,7 +281,8 @@ pub struct SafeCreateTransactionArgs {
     pub payment_receiver: Address,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
 pub enum RelayerTransactionState {
     StateNew,
     StateExecuted,
@@ -151,10 +290,12 @@ pub enum RelayerTransactionState {
     StateInvalid,
     StateConfirmed,
     StateFailed,
+    /// A state this client does not know about, kept verbatim.
+    Unknown(String),
 }
 
 impl RelayerTransactionState {
-    pub fn as_str(&self) -> &'static str {
+    pub fn as_str(&self) -> &str {
         match self {
             RelayerTransactionState::StateNew => "STATE_NEW",
             RelayerTransactionState::StateExecuted => "STATE_EXECUTED",
@@ -162,10 +303,12 @@ impl RelayerTransactionState {
             RelayerTransactionState::StateInvalid => "STATE_INVALID",
             RelayerTransactionState::StateConfirmed => "STATE_CONFIRMED",
             RelayerTransactionState::StateFailed => "STATE_FAILED",
+            RelayerTransactionState::Unknown(s) => s,
         }
     }
 
-    pub fn from_str(s: &str) -> Option<Self> {
+    /// Parses a known state, returning `None` for anything else.
+    pub fn from_known_str(s: &str) -> Option<Self> {
         match s {
             "STATE_NEW" => Some(RelayerTransactionState::StateNew),
             "STATE_EXECUTED" => Some(RelayerTransactionState::StateExecuted),
@@ -178,9 +321,115 @@ impl RelayerTransactionState {
     }
 }
 
+impl From<String> for RelayerTransactionState {
+    fn from(s: String) -> Self {
+        RelayerTransactionState::from_known_str(&s).unwrap_or(RelayerTransactionState::Unknown(s))
+    }
+}
+
+impl From<RelayerTransactionState> for String {
+    fn from(state: RelayerTransactionState) -> Self {
+        match state {
+            RelayerTransactionState::Unknown(s) => s,
+            known => known.as_str().to_string(),
+        }
+    }
+}
+
+/// A transaction as reported by the relayer's `/transaction` and
+/// `/transactions` endpoints.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelayerTransaction {
+    #[serde(rename = "transactionID")]
+    pub transaction_id: String,
+    pub state: RelayerTransactionState,
+    pub transaction_hash: Option<String>,
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub proxy_address: Option<String>,
+    pub data: Option<String>,
+    pub nonce: Option<String>,
+    pub value: Option<String>,
+    pub signature: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub metadata: Option<String>,
+    #[serde(rename = "type")]
+    pub transaction_type: Option<String>,
+}
+
+/// Response of `/submit`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubmitTransactionResponse {
+    #[serde(rename = "transactionID")]
+    pub transaction_id: Option<String>,
+    #[serde(rename = "transactionHash")]
+    pub transaction_hash: Option<String>,
+    pub state: Option<RelayerTransactionState>,
+}
+
+/// Response of `/nonce`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NonceResponse {
+    pub nonce: String,
+}
+
+/// Response of `/relay-payload`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RelayPayload {
+    pub address: String,
+    pub nonce: String,
+}
+
+/// Response of `/deployed`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeployedResponse {
+    #[serde(default)]
+    pub deployed: bool,
+}
+
 #[derive(Debug, Clone)]
 pub struct SplitSig {
     pub r: U256,
     pub s: U256,
     pub v: u8,
 }
+
+#[test]
+fn test_relayer_transaction_deserialize() {
+    let txns: Vec<RelayerTransaction> = serde_json::from_str(
+        r#"[{
+            "transactionID": "0190b317-a1d3-7bec-9b91-eeb6dcd3a620",
+            "transactionHash": "0x5f1b2d0c2b7a1c3ed9f3d1b8d2a0b6f2bbbc5b0e4c7e2c8b0a7b2c1d3e4f5a6b",
+            "from": "0x6e0c80c90ea6c15917308F820Eac91Ce2724B5b5",
+            "to": "0xaacFeEa03eb1561C4e67d661e40682Bd20E3541b",
+            "proxyAddress": "0x6d8c4e9aDF5748Af82Dabe2C6225207770d6B4fa",
+            "data": "0x",
+            "nonce": "",
+            "value": "",
+            "signature": "0x01",
+            "state": "STATE_MINED",
+            "type": "SAFE-CREATE",
+            "metadata": "",
+            "createdAt": "2024-07-10T21:18:26.323406Z",
+            "updatedAt": "2024-07-10T21:18:32.011942Z"
+        }, {
+            "transactionID": "0190b317-a1d3-7bec-9b91-eeb6dcd3a621",
+            "state": "STATE_SOMETHING_NEW"
+        }]"#,
+    )
+    .unwrap();
+
+    assert_eq!(txns[0].state, RelayerTransactionState::StateMined);
+    assert_eq!(txns[0].transaction_type.as_deref(), Some("SAFE-CREATE"));
+    assert!(txns[0].created_at.is_some());
+    assert_eq!(
+        txns[1].state,
+        RelayerTransactionState::Unknown("STATE_SOMETHING_NEW".to_string())
+    );
+    assert_eq!(
+        serde_json::to_value(&txns[1].state).unwrap(),
+        "STATE_SOMETHING_NEW"
+    );
+}
@@ -0,0 +1,310 @@
+use crate::errors::{RelayerApiException, RelayerClientException};
+use ethers::types::Address;
+use std::collections::{BTreeSet, HashMap};
+use std::future::Future;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+
+/// Hands out Safe nonces to concurrent callers of [`crate::RelayClient`].
+///
+/// Nonces are tracked per owner address. The first reservation, and any
+/// reservation made after `resync_interval` or after [`NonceManager::invalidate`],
+/// reads the relayer's `/nonce`; all others are served locally and in
+/// sequence. A nonce released by its reservation is handed to the next
+/// caller, so no gap is left in front of nonces already submitted.
+///
+/// The relayer's `/nonce` is assumed to account for every transaction it has
+/// accepted. When it is ahead of the local state it wins; nonces still
+/// reserved by in-flight callers are never handed out twice.
+#[derive(Debug)]
+pub struct NonceManager {
+    resync_interval: Option<Duration>,
+    states: Mutex<HashMap<Address, NonceState>>,
+}
+
+#[derive(Debug, Default)]
+struct NonceState {
+    /// Next nonce that has never been handed out.
+    next: u64,
+    /// Reserved nonces whose transactions have not been submitted yet.
+    in_flight: BTreeSet<u64>,
+    /// Nonces below `next` that were released and must be reused first.
+    released: BTreeSet<u64>,
+    /// When `/nonce` was last read; `None` forces a resync.
+    synced_at: Option<Instant>,
+}
+
+impl NonceState {
+    fn is_stale(&self, resync_interval: Option<Duration>) -> bool {
+        match (self.synced_at, resync_interval) {
+            (None, _) => true,
+            (Some(synced_at), Some(interval)) => synced_at.elapsed() >= interval,
+            (Some(_), None) => false,
+        }
+    }
+
+    fn sync(&mut self, remote: u64) {
+        let floor = self.in_flight.last().map_or(remote, |n| remote.max(n + 1));
+        self.next = floor;
+        // Released nonces below the relayer's nonce were used elsewhere
+        self.released = self
+            .released
+            .range(remote..floor)
+            .filter(|n| !self.in_flight.contains(n))
+            .copied()
+            .collect();
+        self.synced_at = Some(Instant::now());
+    }
+
+    fn take(&mut self) -> u64 {
+        let nonce = self.released.pop_first().unwrap_or_else(|| {
+            self.next += 1;
+            self.next - 1
+        });
+        self.in_flight.insert(nonce);
+        nonce
+    }
+
+    fn release(&mut self, nonce: u64) {
+        if !self.in_flight.remove(&nonce) {
+            return;
+        }
+        self.released.insert(nonce);
+        // Shrink back instead of leaving released nonces at the top
+        while self.next > 0 && self.released.remove(&(self.next - 1)) {
+            self.next -= 1;
+        }
+    }
+}
+
+impl Default for NonceManager {
+    fn default() -> Self {
+        NonceManager::new(Some(Duration::from_secs(60)))
+    }
+}
+
+impl NonceManager {
+    /// `resync_interval` bounds how long local state is trusted before
+    /// `/nonce` is read again; `None` only resyncs on conflicts.
+    pub fn new(resync_interval: Option<Duration>) -> Self {
+        NonceManager {
+            resync_interval,
+            states: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn resync_interval(&self) -> Option<Duration> {
+        self.resync_interval
+    }
+
+    fn states(&self) -> MutexGuard<'_, HashMap<Address, NonceState>> {
+        self.states.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Reserves the next nonce of `owner`, calling `fetch` for the relayer's
+    /// nonce when the local state is missing or stale. The reservation is
+    /// released when dropped unless [`NonceReservation::commit`] is called.
+    pub async fn reserve<F, Fut>(
+        &self,
+        owner: Address,
+        fetch: F,
+    ) -> Result<NonceReservation<'_>, RelayerClientException>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<String, RelayerClientException>>,
+    {
+        let stale = self
+            .states()
+            .get(&owner)
+            .is_none_or(|state| state.is_stale(self.resync_interval));
+
+        // Fetched without holding the lock; concurrent syncs agree on the result
+        let remote = if stale {
+            let nonce = fetch().await?;
+            Some(nonce.parse::<u64>().map_err(|e| {
+                RelayerClientException::NonceFetch(RelayerApiException::InvalidResponse(format!(
+                    "Invalid nonce {}: {}",
+                    nonce, e
+                )))
+            })?)
+        } else {
+            None
+        };
+
+        let mut states = self.states();
+        let state = states.entry(owner).or_default();
+        if let Some(remote) = remote {
+            state.sync(remote);
+        }
+        Ok(NonceReservation {
+            manager: self,
+            owner,
+            nonce: state.take(),
+            committed: false,
+        })
+    }
+
+    /// Forces the next reservation for `owner` to resync from `/nonce`.
+    pub fn invalidate(&self, owner: &Address) {
+        if let Some(state) = self.states().get_mut(owner) {
+            state.synced_at = None;
+        }
+    }
+
+    /// Drops all local state.
+    pub fn reset(&self) {
+        self.states().clear();
+    }
+
+    fn release(&self, owner: &Address, nonce: u64) {
+        if let Some(state) = self.states().get_mut(owner) {
+            state.release(nonce);
+        }
+    }
+
+    fn commit(&self, owner: &Address, nonce: u64) {
+        if let Some(state) = self.states().get_mut(owner) {
+            state.in_flight.remove(&nonce);
+        }
+    }
+}
+
+/// A nonce reserved from a [`NonceManager`], released on drop unless
+/// committed.
+#[derive(Debug)]
+pub struct NonceReservation<'a> {
+    manager: &'a NonceManager,
+    owner: Address,
+    nonce: u64,
+    committed: bool,
+}
+
+impl NonceReservation<'_> {
+    pub fn nonce(&self) -> u64 {
+        self.nonce
+    }
+
+    /// Marks the nonce as used by a submitted transaction.
+    pub fn commit(mut self) {
+        self.committed = true;
+        self.manager.commit(&self.owner, self.nonce);
+    }
+}
+
+impl Drop for NonceReservation<'_> {
+    fn drop(&mut self) {
+        if !self.committed {
+            self.manager.release(&self.owner, self.nonce);
+        }
+    }
+}
+
+/// Whether the relayer rejected a submission because its nonce was already
+/// used or is otherwise out of sequence.
+pub(crate) fn is_nonce_conflict(error: &RelayerClientException) -> bool {
+    match error {
+        RelayerClientException::Api(RelayerApiException::ApiError {
+            status_code: Some(400 | 409),
+            error_msg,
+            relayer_error,
+        }) => relayer_error
+            .as_deref()
+            .unwrap_or(error_msg)
+            .to_lowercase()
+            .contains("nonce"),
+        _ => false,
+    }
+}
+
+/// Whether a failed submission was provably not accepted by the relayer:
+/// it failed before being sent, the connection could not be made, or the
+/// relayer answered with a 4xx. Its nonce can then be reused.
+pub(crate) fn is_rejected_submission(error: &RelayerClientException) -> bool {
+    match error {
+        RelayerClientException::Api(RelayerApiException::ConnectionFailed(_)) => true,
+        RelayerClientException::Api(RelayerApiException::ApiError { status_code, .. }) => {
+            matches!(status_code, Some(400..=499))
+        }
+        // Timeouts, dropped connections, 5xx and unreadable answers
+        RelayerClientException::Api(_) => false,
+        _ => true,
+    }
+}
+
+#[tokio::test]
+async fn test_nonce_manager_reserves_releases_and_resyncs() {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    let fetches = AtomicU32::new(0);
+    let fetch = |nonce: &'static str| {
+        let fetches = &fetches;
+        move || async move {
+            fetches.fetch_add(1, Ordering::SeqCst);
+            Ok(nonce.to_string())
+        }
+    };
+    let manager = NonceManager::new(None);
+    let owner = Address::repeat_byte(1);
+
+    // Concurrent callers get sequential nonces from a single fetch
+    let (a, b) = tokio::join!(manager.reserve(owner, fetch("5")), async {
+        tokio::task::yield_now().await;
+        manager.reserve(owner, fetch("5")).await
+    });
+    let (a, b) = (a.unwrap(), b.unwrap());
+    let mut nonces = [a.nonce(), b.nonce()];
+    nonces.sort();
+    assert_eq!(nonces, [5, 6]);
+    let c = manager.reserve(owner, fetch("5")).await.unwrap();
+    assert_eq!(c.nonce(), 7);
+    assert!(fetches.load(Ordering::SeqCst) <= 2);
+
+    // A failed nonce in the middle is handed out next
+    let (low, high) = if a.nonce() == 5 { (a, b) } else { (b, a) };
+    low.commit();
+    drop(high);
+    c.commit();
+    let d = manager.reserve(owner, fetch("5")).await.unwrap();
+    assert_eq!(d.nonce(), 6);
+
+    // Releasing the top nonce shrinks the sequence back
+    let e = manager.reserve(owner, fetch("5")).await.unwrap();
+    assert_eq!(e.nonce(), 8);
+    drop(e);
+    d.commit();
+    assert_eq!(manager.reserve(owner, fetch("5")).await.unwrap().nonce(), 8);
+
+    // A conflict resyncs from the relayer
+    manager.invalidate(&owner);
+    let before = fetches.load(Ordering::SeqCst);
+    let f = manager.reserve(owner, fetch("12")).await.unwrap();
+    assert_eq!(f.nonce(), 12);
+    assert_eq!(fetches.load(Ordering::SeqCst), before + 1);
+
+    assert!(is_nonce_conflict(&RelayerClientException::Api(
+        RelayerApiException::new(Some(400), r#"{"error":"invalid nonce"}"#.to_string())
+    )));
+    assert!(!is_nonce_conflict(&RelayerClientException::Api(
+        RelayerApiException::new(Some(500), "nonce".to_string())
+    )));
+
+    assert!(is_rejected_submission(&RelayerClientException::Api(
+        RelayerApiException::ConnectionFailed("refused".to_string())
+    )));
+    assert!(is_rejected_submission(&RelayerClientException::Api(
+        RelayerApiException::new(Some(401), "unauthorized".to_string())
+    )));
+    assert!(is_rejected_submission(
+        &RelayerClientException::MissingBuilderCredentials
+    ));
+    for ambiguous in [
+        RelayerApiException::Timeout("timed out".to_string()),
+        RelayerApiException::RequestException("reset".to_string()),
+        RelayerApiException::new(Some(502), "bad gateway".to_string()),
+        RelayerApiException::InvalidResponse("truncated".to_string()),
+    ] {
+        assert!(!is_rejected_submission(&RelayerClientException::Api(
+            ambiguous
+        )));
+    }
+}
@@ -0,0 +1,172 @@
+//! Approvals a Safe needs before it can trade on Polymarket.
+
+use crate::config::{ContractConfig, get_contract_config};
+use crate::encode::contract_call;
+use crate::errors::RelayerClientException;
+use crate::models::SafeTransaction;
+use ethabi::Token;
+use ethers::types::{Address, U256};
+
+/// `approve(spender, type(uint256).max)` on the ERC-20 `token`.
+pub fn approve_max(token: Address, spender: Address) -> SafeTransaction {
+    contract_call(
+        token,
+        "approve(address,uint256)",
+        &[Token::Address(spender), Token::Uint(U256::MAX)],
+    )
+}
+
+/// `setApprovalForAll(operator, true)` on the ERC-1155 `token`.
+pub fn set_approval_for_all(token: Address, operator: Address) -> SafeTransaction {
+    contract_call(
+        token,
+        "setApprovalForAll(address,bool)",
+        &[Token::Address(operator), Token::Bool(true)],
+    )
+}
+
+/// The trading approvals for the built-in configuration of `chain_id`, see
+/// [`trading_approvals_for`]. A config from
+/// [`crate::config::load_contract_configs`] is not consulted here; pass it
+/// (or [`crate::RelayClient::contract_config`]) to [`trading_approvals_for`].
+pub fn trading_approvals(chain_id: u64) -> Result<Vec<SafeTransaction>, RelayerClientException> {
+    trading_approvals_for(&get_contract_config(chain_id)?)
+}
+
+/// Collateral `approve` and Conditional Tokens `setApprovalForAll` for each
+/// of the CTF Exchange, NegRisk CTF Exchange and NegRisk Adapter, in that
+/// order. All three spenders are required: a config missing any of them,
+/// such as the built-in Amoy one, is an [`InvalidConfig`] rather than a
+/// partial set of approvals.
+///
+/// [`InvalidConfig`]: RelayerClientException::InvalidConfig
+pub fn trading_approvals_for(
+    config: &ContractConfig,
+) -> Result<Vec<SafeTransaction>, RelayerClientException> {
+    let (Some(collateral), Some(conditional_tokens)) =
+        (config.collateral, config.conditional_tokens)
+    else {
+        return Err(RelayerClientException::InvalidConfig(
+            "collateral and conditional_tokens are required for trading approvals".to_string(),
+        ));
+    };
+
+    let spenders = [
+        ("ctf_exchange", config.ctf_exchange),
+        ("neg_risk_ctf_exchange", config.neg_risk_ctf_exchange),
+        ("neg_risk_adapter", config.neg_risk_adapter),
+    ]
+    .into_iter()
+    .map(|(name, spender)| {
+        spender.ok_or_else(|| {
+            RelayerClientException::InvalidConfig(format!(
+                "{name} is required for trading approvals"
+            ))
+        })
+    })
+    .collect::<Result<Vec<Address>, _>>()?;
+
+    Ok(approvals(collateral, conditional_tokens, &spenders))
+}
+
+/// Like [`trading_approvals_for`], but for whichever of the three spenders
+/// `config` has, e.g. only the CTF Exchange with the built-in Amoy config.
+/// Fails only when none of them is configured.
+pub fn available_trading_approvals_for(
+    config: &ContractConfig,
+) -> Result<Vec<SafeTransaction>, RelayerClientException> {
+    let (Some(collateral), Some(conditional_tokens)) =
+        (config.collateral, config.conditional_tokens)
+    else {
+        return Err(RelayerClientException::InvalidConfig(
+            "collateral and conditional_tokens are required for trading approvals".to_string(),
+        ));
+    };
+
+    let spenders: Vec<Address> = [
+        config.ctf_exchange,
+        config.neg_risk_ctf_exchange,
+        config.neg_risk_adapter,
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    if spenders.is_empty() {
+        return Err(RelayerClientException::InvalidConfig(
+            "no exchange is configured for trading approvals".to_string(),
+        ));
+    }
+
+    Ok(approvals(collateral, conditional_tokens, &spenders))
+}
+
+fn approvals(
+    collateral: Address,
+    conditional_tokens: Address,
+    spenders: &[Address],
+) -> Vec<SafeTransaction> {
+    spenders
+        .iter()
+        .map(|spender| approve_max(collateral, *spender))
+        .chain(
+            spenders
+                .iter()
+                .map(|spender| set_approval_for_all(conditional_tokens, *spender)),
+        )
+        .collect()
+}
+
+#[test]
+fn test_trading_approvals() {
+    use crate::models::OperationType;
+
+    let config = get_contract_config(137).unwrap();
+    let approvals = trading_approvals(137).unwrap();
+    assert_eq!(approvals.len(), 6);
+    assert!(
+        approvals
+            .iter()
+            .all(|txn| txn.operation == OperationType::Call && txn.value == "0")
+    );
+
+    let usdc_to_exchange = &approvals[0];
+    assert_eq!(Some(usdc_to_exchange.to), config.collateral);
+    assert_eq!(
+        usdc_to_exchange.data,
+        "0x095ea7b3\
+         0000000000000000000000004bfb41d5b3570defd03c39a9a4d8de6bd8b8982e\
+         ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff"
+    );
+    let ctf_to_adapter = &approvals[5];
+    assert_eq!(Some(ctf_to_adapter.to), config.conditional_tokens);
+    assert_eq!(
+        ctf_to_adapter.data,
+        "0xa22cb465\
+         000000000000000000000000d91e80cf2e7be2e162c6513ced06f1dd0da35296\
+         0000000000000000000000000000000000000000000000000000000000000001"
+    );
+
+    // Amoy has no NegRisk deployments in the built-in config
+    match trading_approvals(80002) {
+        Err(RelayerClientException::InvalidConfig(message)) => {
+            assert!(message.contains("neg_risk_ctf_exchange"))
+        }
+        other => panic!("expected InvalidConfig, got {other:?}"),
+    }
+    let amoy = get_contract_config(80002).unwrap();
+    let available = available_trading_approvals_for(&amoy).unwrap();
+    assert_eq!(available.len(), 2);
+    assert_eq!(
+        available[0],
+        approve_max(amoy.collateral.unwrap(), amoy.ctf_exchange.unwrap())
+    );
+    assert_eq!(
+        available[1],
+        set_approval_for_all(amoy.conditional_tokens.unwrap(), amoy.ctf_exchange.unwrap())
+    );
+    assert_eq!(available_trading_approvals_for(&config).unwrap(), approvals);
+    assert!(matches!(
+        trading_approvals_for(&ContractConfig::new(Address::zero(), Address::zero())),
+        Err(RelayerClientException::InvalidConfig(_))
+    ));
+}
@@ -0,0 +1,318 @@
+//! Offline `getConditionId`, `getCollectionId` and `getPositionId`, matching
+//! the ConditionalTokens contract bit for bit.
+//!
+//! Collection ids are compressed points on alt_bn128, so that combining
+//! collections is a point addition and does not depend on order.
+
+use crate::errors::RelayerClientException;
+use ethers::core::utils::keccak256;
+use ethers::types::{Address, H256, U256, U512};
+
+/// The alt_bn128 base field modulus.
+const P: U256 = U256([
+    0x3c208c16d87cfd47,
+    0x97816a916871ca8d,
+    0xb85045b68181585d,
+    0x30644e72e131a029,
+]);
+
+/// `y^2 = x^3 + B`
+const B: u64 = 3;
+
+/// `keccak256(abi.encodePacked(oracle, questionId, outcomeSlotCount))`
+pub fn condition_id(oracle: Address, question_id: H256, outcome_slot_count: u32) -> H256 {
+    let mut packed = Vec::with_capacity(20 + 32 + 32);
+    packed.extend_from_slice(oracle.as_bytes());
+    packed.extend_from_slice(question_id.as_bytes());
+    packed.extend_from_slice(&u256_bytes(U256::from(outcome_slot_count)));
+    H256(keccak256(packed))
+}
+
+/// The collection of `index_set` in `condition_id`, nested under
+/// `parent_collection_id` (zero for a top-level position). Fails like the
+/// contract when the parent is not a valid collection id.
+pub fn collection_id(
+    parent_collection_id: H256,
+    condition_id: H256,
+    index_set: U256,
+) -> Result<H256, RelayerClientException> {
+    let (mut x1, mut y1) = outcome_point(condition_id, index_set);
+
+    let mut x2 = U256::from_big_endian(parent_collection_id.as_bytes());
+    if !x2.is_zero() {
+        // The parent is compressed the same way: the top two bits hold y's
+        // parity, and like the contract either one being set means odd
+        let odd = !(x2 >> 254).is_zero();
+        x2 = (x2 << 2) >> 2;
+        let yy = curve_rhs(x2);
+        let mut y2 = sqrt_mod(yy);
+        if odd != y2.bit(0) {
+            y2 = P - y2;
+        }
+        // The `ecAdd` precompile also refuses coordinates outside the field
+        if mul_mod(y2, y2) != yy || x2 >= P {
+            return Err(RelayerClientException::encoding(
+                "invalid parent collection ID",
+            ));
+        }
+        (x1, y1) = ec_add((x1, y1), (x2, y2));
+    }
+
+    Ok(compress((x1, y1)))
+}
+
+/// The curve point of `index_set` in `condition_id`: the first x above the
+/// hash with a square root, and the y whose parity is the hash's top bit.
+fn outcome_point(condition_id: H256, index_set: U256) -> (U256, U256) {
+    let mut packed = condition_id.as_bytes().to_vec();
+    packed.extend_from_slice(&u256_bytes(index_set));
+    let hash = U256::from_big_endian(&keccak256(packed));
+
+    let odd = hash.bit(255);
+    let mut x = hash % P;
+    let mut y;
+    loop {
+        x = add_mod(x, U256::one());
+        let yy = curve_rhs(x);
+        y = sqrt_mod(yy);
+        if mul_mod(y, y) == yy {
+            break;
+        }
+    }
+    if odd != y.bit(0) {
+        y = P - y;
+    }
+    (x, y)
+}
+
+/// `x` with y's parity in bit 254, as the contract stores collection ids.
+fn compress((mut x, y): (U256, U256)) -> H256 {
+    if y.bit(0) {
+        x ^= U256::one() << 254;
+    }
+    H256(u256_bytes(x))
+}
+
+/// `uint256(keccak256(abi.encodePacked(collateralToken, collectionId)))`,
+/// the ERC-1155 token id. Positions of negative-risk markets are held in the
+/// NegRisk Adapter's wrapped collateral, not in USDC.e.
+pub fn position_id(collateral: Address, collection_id: H256) -> U256 {
+    let mut packed = collateral.as_bytes().to_vec();
+    packed.extend_from_slice(collection_id.as_bytes());
+    U256::from_big_endian(&keccak256(packed))
+}
+
+/// The YES and NO token ids of a top-level binary condition.
+pub fn binary_position_ids(collateral: Address, condition_id: H256) -> [U256; 2] {
+    super::binary_partition()
+        .map(|index_set| position_id(collateral, compress(outcome_point(condition_id, index_set))))
+}
+
+fn u256_bytes(value: U256) -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    value.to_big_endian(&mut bytes);
+    bytes
+}
+
+fn add_mod(a: U256, b: U256) -> U256 {
+    let (sum, overflow) = a.overflowing_add(b);
+    if overflow || sum >= P {
+        sum.overflowing_sub(P).0
+    } else {
+        sum
+    }
+}
+
+fn sub_mod(a: U256, b: U256) -> U256 {
+    add_mod(a, P - b % P)
+}
+
+fn mul_mod(a: U256, b: U256) -> U256 {
+    // Reduced below P, so only the low half is set
+    let mut bytes = [0u8; 64];
+    (a.full_mul(b) % U512::from(P)).to_big_endian(&mut bytes);
+    U256::from_big_endian(&bytes[32..])
+}
+
+fn pow_mod(base: U256, exponent: U256) -> U256 {
+    let mut result = U256::one();
+    for i in (0..exponent.bits()).rev() {
+        result = mul_mod(result, result);
+        if exponent.bit(i) {
+            result = mul_mod(result, base);
+        }
+    }
+    result
+}
+
+/// A square root of `a` when it has one; P is 3 mod 4.
+fn sqrt_mod(a: U256) -> U256 {
+    pow_mod(a, (P + 1) / 4)
+}
+
+fn curve_rhs(x: U256) -> U256 {
+    add_mod(mul_mod(x, mul_mod(x, x)), U256::from(B))
+}
+
+/// The `ecAdd` precompile, with the point at infinity as `(0, 0)`.
+fn ec_add(a: (U256, U256), b: (U256, U256)) -> (U256, U256) {
+    let infinity = (U256::zero(), U256::zero());
+    if a == infinity {
+        return b;
+    }
+    if b == infinity {
+        return a;
+    }
+    let slope = if a.0 == b.0 {
+        if add_mod(a.1, b.1).is_zero() {
+            return infinity;
+        }
+        let numerator = mul_mod(U256::from(3), mul_mod(a.0, a.0));
+        mul_mod(numerator, pow_mod(add_mod(a.1, a.1), P - 2))
+    } else {
+        mul_mod(sub_mod(b.1, a.1), pow_mod(sub_mod(b.0, a.0), P - 2))
+    };
+    let x = sub_mod(sub_mod(mul_mod(slope, slope), a.0), b.0);
+    let y = sub_mod(mul_mod(slope, sub_mod(a.0, x)), a.1);
+    (x, y)
+}
+
+#[test]
+fn test_position_ids_of_known_markets() {
+    // "Presidential Election Winner 2024: Donald Trump", a negative-risk
+    // market, so its positions are in the NegRisk wrapped collateral
+    let wrapped_collateral: Address = "0x3A3BD7bb9528E159577F7C2e685CC81A765002E2"
+        .parse()
+        .unwrap();
+    let condition: H256 = "0xdd22472e552920b8438158ea7238bfadfa4f736aa4cee91a6b86c39ead110917"
+        .parse()
+        .unwrap();
+    assert_eq!(
+        binary_position_ids(wrapped_collateral, condition),
+        [
+            U256::from_dec_str(
+                "21742633143463906290569050155826241533067272736897614950488156847949938836455"
+            )
+            .unwrap(),
+            U256::from_dec_str(
+                "48331043336612883890938759509493159234755048973500640148014422747788308965732"
+            )
+            .unwrap(),
+        ]
+    );
+}
+
+#[test]
+fn test_ids_of_a_usdc_market() {
+    // Expected values from a Python transcription of CTHelpers, not read
+    // from chain
+    let oracle = Address::repeat_byte(0x11);
+    let condition = condition_id(oracle, H256::repeat_byte(0x01), 2);
+    assert_eq!(
+        condition,
+        "0x6b8207983254c3932c942a77a78224bd161938b051b5230d4dd675930917b706"
+            .parse::<H256>()
+            .unwrap()
+    );
+
+    let usdc: Address = "0x2791Bca1f2de4661ED88A30C99A7a9449Aa84174"
+        .parse()
+        .unwrap();
+    assert_eq!(
+        binary_position_ids(usdc, condition),
+        [
+            U256::from_dec_str(
+                "111519229220515281141072481059713101580610195005066836205713828989462138169808"
+            )
+            .unwrap(),
+            U256::from_dec_str(
+                "86412926930882983465825302980832620871020603655629109672073378464087277325193"
+            )
+            .unwrap(),
+        ]
+    );
+
+    // Nested under the YES collection of `condition`
+    let parent = collection_id(H256::zero(), condition, U256::from(1)).unwrap();
+    assert_eq!(
+        parent,
+        "0x21f998ceec1cf7ca859a6090eb9d072d298ecffe69fee360c9a23ae0bf5ff2e3"
+            .parse::<H256>()
+            .unwrap()
+    );
+    let other = condition_id(oracle, H256::repeat_byte(0x02), 3);
+    let nested = collection_id(parent, other, U256::from(6)).unwrap();
+    assert_eq!(
+        nested,
+        "0x2fc946887716778435cbb889e3ff6cc83441a1ff0f090524f1d95cbbf9d19183"
+            .parse::<H256>()
+            .unwrap()
+    );
+    assert_eq!(
+        position_id(usdc, nested),
+        U256::from_dec_str(
+            "47030947650243001403100554557440245844306718664073579760138919176566935576628"
+        )
+        .unwrap()
+    );
+
+    // An odd parent, flagged by bit 254, and the same x flagged by bit 255
+    // instead are the same point to the contract
+    let odd_parent = collection_id(H256::zero(), condition, U256::from(2)).unwrap();
+    let flags = U256::from_big_endian(odd_parent.as_bytes()) >> 254;
+    assert_eq!(flags, U256::one());
+    let high_bit = H256(u256_bytes(
+        U256::from_big_endian(odd_parent.as_bytes()) ^ (U256::from(3) << 254),
+    ));
+    assert_eq!(
+        collection_id(high_bit, other, U256::from(6)).unwrap(),
+        "0x2f1c6d4cf36a57f58868f2830bb49e6c0ae1ddd15318838e020caae340fa1383"
+            .parse::<H256>()
+            .unwrap()
+    );
+    assert_eq!(
+        collection_id(odd_parent, other, U256::from(6)).unwrap(),
+        collection_id(high_bit, other, U256::from(6)).unwrap()
+    );
+}
+
+#[test]
+fn test_nested_collection_ids() {
+    let oracle = Address::repeat_byte(0x11);
+    let a = condition_id(oracle, H256::repeat_byte(0x01), 2);
+    let b = condition_id(oracle, H256::repeat_byte(0x02), 3);
+    assert_ne!(a, b);
+    assert_ne!(a, condition_id(oracle, H256::repeat_byte(0x01), 3));
+
+    // Nesting is a point addition, so the order of conditions is irrelevant
+    let a_then_b = collection_id(
+        collection_id(H256::zero(), a, U256::from(1)).unwrap(),
+        b,
+        U256::from(6),
+    )
+    .unwrap();
+    let b_then_a = collection_id(
+        collection_id(H256::zero(), b, U256::from(6)).unwrap(),
+        a,
+        U256::from(1),
+    )
+    .unwrap();
+    assert_eq!(a_then_b, b_then_a);
+    assert_ne!(
+        a_then_b,
+        collection_id(H256::zero(), a, U256::from(1)).unwrap()
+    );
+
+    // A parent whose x is not on the curve is rejected like on-chain
+    let off_curve = (1u64..)
+        .map(U256::from)
+        .find(|x| {
+            let yy = curve_rhs(*x);
+            mul_mod(sqrt_mod(yy), sqrt_mod(yy)) != yy
+        })
+        .unwrap();
+    assert!(matches!(
+        collection_id(H256(u256_bytes(off_curve)), a, U256::from(1)),
+        Err(RelayerClientException::Encoding(_))
+    ));
+}
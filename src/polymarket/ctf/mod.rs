@@ -0,0 +1,240 @@
+//! Calls on the Gnosis Conditional Tokens Framework contract.
+//!
+//! Polymarket markets are top-level binary conditions: the parent collection
+//! is [`H256::zero`] and the partition is `[1, 2]` (YES, NO), see
+//! [`binary_partition`].
+
+pub mod ids;
+
+use crate::config::ContractConfig;
+use crate::encode::contract_call;
+use crate::errors::RelayerClientException;
+use crate::models::SafeTransaction;
+use ethabi::Token;
+use ethers::types::{Address, H256, U256};
+
+/// The index sets of the YES and NO outcomes of a binary condition.
+pub fn binary_partition() -> [U256; 2] {
+    [U256::from(1), U256::from(2)]
+}
+
+/// Builds calls on a ConditionalTokens deployment for one collateral token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConditionalTokens {
+    pub address: Address,
+    pub collateral: Address,
+}
+
+impl ConditionalTokens {
+    pub fn new(address: Address, collateral: Address) -> Self {
+        ConditionalTokens {
+            address,
+            collateral,
+        }
+    }
+
+    /// The Conditional Tokens and collateral of `config`.
+    pub fn from_config(config: &ContractConfig) -> Result<Self, RelayerClientException> {
+        match (config.conditional_tokens, config.collateral) {
+            (Some(address), Some(collateral)) => Ok(ConditionalTokens::new(address, collateral)),
+            _ => Err(RelayerClientException::InvalidConfig(
+                "collateral and conditional_tokens are required for CTF calls".to_string(),
+            )),
+        }
+    }
+
+    /// `splitPosition`: locks `amount` of the parent position (or collateral
+    /// for a zero `parent_collection_id`) and mints `amount` of each
+    /// position in `partition`.
+    pub fn split_position(
+        &self,
+        parent_collection_id: H256,
+        condition_id: H256,
+        partition: &[U256],
+        amount: U256,
+    ) -> Result<SafeTransaction, RelayerClientException> {
+        check_partition(partition)?;
+        Ok(self.position_call(
+            "splitPosition(address,bytes32,bytes32,uint256[],uint256)",
+            parent_collection_id,
+            condition_id,
+            partition,
+            Some(amount),
+        ))
+    }
+
+    /// `mergePositions`: the inverse of [`ConditionalTokens::split_position`].
+    pub fn merge_positions(
+        &self,
+        parent_collection_id: H256,
+        condition_id: H256,
+        partition: &[U256],
+        amount: U256,
+    ) -> Result<SafeTransaction, RelayerClientException> {
+        check_partition(partition)?;
+        Ok(self.position_call(
+            "mergePositions(address,bytes32,bytes32,uint256[],uint256)",
+            parent_collection_id,
+            condition_id,
+            partition,
+            Some(amount),
+        ))
+    }
+
+    /// `redeemPositions`: burns the Safe's whole balance of each of
+    /// `index_sets` in a resolved condition and pays out its share.
+    pub fn redeem_positions(
+        &self,
+        parent_collection_id: H256,
+        condition_id: H256,
+        index_sets_to_redeem: &[U256],
+    ) -> Result<SafeTransaction, RelayerClientException> {
+        if index_sets_to_redeem.is_empty() || index_sets_to_redeem.iter().any(U256::is_zero) {
+            return Err(RelayerClientException::encoding(
+                "index sets to redeem must be non-empty and non-zero",
+            ));
+        }
+        Ok(self.position_call(
+            "redeemPositions(address,bytes32,bytes32,uint256[])",
+            parent_collection_id,
+            condition_id,
+            index_sets_to_redeem,
+            None,
+        ))
+    }
+
+    /// Splits `amount` of collateral into YES and NO of a binary market.
+    pub fn split_binary(&self, condition_id: H256, amount: U256) -> SafeTransaction {
+        self.position_call(
+            "splitPosition(address,bytes32,bytes32,uint256[],uint256)",
+            H256::zero(),
+            condition_id,
+            &binary_partition(),
+            Some(amount),
+        )
+    }
+
+    /// Merges `amount` of YES and NO of a binary market back into collateral.
+    pub fn merge_binary(&self, condition_id: H256, amount: U256) -> SafeTransaction {
+        self.position_call(
+            "mergePositions(address,bytes32,bytes32,uint256[],uint256)",
+            H256::zero(),
+            condition_id,
+            &binary_partition(),
+            Some(amount),
+        )
+    }
+
+    /// Redeems both outcomes of a resolved binary market.
+    pub fn redeem_binary(&self, condition_id: H256) -> SafeTransaction {
+        self.position_call(
+            "redeemPositions(address,bytes32,bytes32,uint256[])",
+            H256::zero(),
+            condition_id,
+            &binary_partition(),
+            None,
+        )
+    }
+
+    /// Encodes a call taking `(collateral, parent, condition, index sets)`
+    /// and, for split and merge, an amount. The index sets must already be
+    /// valid for `signature`.
+    fn position_call(
+        &self,
+        signature: &str,
+        parent_collection_id: H256,
+        condition_id: H256,
+        sets: &[U256],
+        amount: Option<U256>,
+    ) -> SafeTransaction {
+        let mut args = vec![
+            Token::Address(self.collateral),
+            Token::FixedBytes(parent_collection_id.as_bytes().to_vec()),
+            Token::FixedBytes(condition_id.as_bytes().to_vec()),
+            index_sets(sets),
+        ];
+        args.extend(amount.map(Token::Uint));
+        contract_call(self.address, signature, &args)
+    }
+}
+
+fn index_sets(sets: &[U256]) -> Token {
+    Token::Array(sets.iter().copied().map(Token::Uint).collect())
+}
+
+/// The contract requires at least two non-empty, disjoint index sets.
+fn check_partition(partition: &[U256]) -> Result<(), RelayerClientException> {
+    if partition.len() < 2 {
+        return Err(RelayerClientException::encoding(
+            "partition needs at least two index sets",
+        ));
+    }
+    let mut union = U256::zero();
+    for set in partition {
+        if set.is_zero() {
+            return Err(RelayerClientException::encoding(
+                "partition contains an empty index set",
+            ));
+        }
+        if !(union & *set).is_zero() {
+            return Err(RelayerClientException::encoding(
+                "partition index sets are not disjoint",
+            ));
+        }
+        union |= *set;
+    }
+    Ok(())
+}
+
+#[test]
+fn test_ctf_calldata() {
+    let ctf =
+        ConditionalTokens::from_config(&crate::config::get_contract_config(137).unwrap()).unwrap();
+    let condition_id = H256::repeat_byte(0xab);
+
+    let split = ctf.split_binary(condition_id, U256::from(1_000_000));
+    assert_eq!(split.to, ctf.address);
+    assert_eq!(
+        split.data,
+        "0x72ce4275\
+         0000000000000000000000002791bca1f2de4661ed88a30c99a7a9449aa84174\
+         0000000000000000000000000000000000000000000000000000000000000000\
+         abababababababababababababababababababababababababababababababab\
+         00000000000000000000000000000000000000000000000000000000000000a0\
+         00000000000000000000000000000000000000000000000000000000000f4240\
+         0000000000000000000000000000000000000000000000000000000000000002\
+         0000000000000000000000000000000000000000000000000000000000000001\
+         0000000000000000000000000000000000000000000000000000000000000002"
+    );
+    assert!(
+        ctf.merge_binary(condition_id, U256::from(1_000_000))
+            .data
+            .starts_with("0x9e7212ad")
+    );
+    let redeem = ctf.redeem_binary(condition_id);
+    assert!(redeem.data.starts_with("0x01b7037c"));
+    assert_eq!(redeem.data.len(), 2 + 8 + 64 * 7);
+
+    // Splitting a nested position into three outcomes
+    let parent = H256::repeat_byte(0x01);
+    let three = [U256::from(1), U256::from(2), U256::from(4)];
+    assert!(
+        ctf.split_position(parent, condition_id, &three, U256::one())
+            .is_ok()
+    );
+
+    for partition in [
+        &[U256::from(3)][..],
+        &[U256::from(1), U256::zero()],
+        &[U256::from(1), U256::from(3)],
+    ] {
+        assert!(matches!(
+            ctf.split_position(H256::zero(), condition_id, partition, U256::one()),
+            Err(RelayerClientException::Encoding(_))
+        ));
+    }
+    assert!(
+        ctf.redeem_positions(H256::zero(), condition_id, &[])
+            .is_err()
+    );
+}
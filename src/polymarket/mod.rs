@@ -0,0 +1,5 @@
+//! Ready-made transactions for Polymarket's contracts.
+
+pub mod approvals;
+pub mod ctf;
+pub mod neg_risk;
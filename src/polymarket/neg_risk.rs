@@ -0,0 +1,142 @@
+//! Calls on the NegRisk Adapter, which wraps the collateral of negative-risk
+//! markets and always splits on the binary partition of a question.
+
+use crate::config::ContractConfig;
+use crate::encode::contract_call;
+use crate::errors::RelayerClientException;
+use crate::models::SafeTransaction;
+use ethabi::Token;
+use ethers::types::{Address, H256, U256};
+
+/// Builds calls on a NegRisk Adapter deployment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NegRiskAdapter {
+    pub address: Address,
+}
+
+impl NegRiskAdapter {
+    pub fn new(address: Address) -> Self {
+        NegRiskAdapter { address }
+    }
+
+    /// The adapter of `config`.
+    pub fn from_config(config: &ContractConfig) -> Result<Self, RelayerClientException> {
+        config
+            .neg_risk_adapter
+            .map(NegRiskAdapter::new)
+            .ok_or_else(|| {
+                RelayerClientException::InvalidConfig(
+                    "neg_risk_adapter is required for NegRisk calls".to_string(),
+                )
+            })
+    }
+
+    /// `splitPosition`: splits `amount` of collateral into YES and NO of the
+    /// question's condition.
+    pub fn split_position(&self, condition_id: H256, amount: U256) -> SafeTransaction {
+        contract_call(
+            self.address,
+            "splitPosition(bytes32,uint256)",
+            &[
+                Token::FixedBytes(condition_id.as_bytes().to_vec()),
+                Token::Uint(amount),
+            ],
+        )
+    }
+
+    /// `mergePositions`: the inverse of [`NegRiskAdapter::split_position`].
+    pub fn merge_positions(&self, condition_id: H256, amount: U256) -> SafeTransaction {
+        contract_call(
+            self.address,
+            "mergePositions(bytes32,uint256)",
+            &[
+                Token::FixedBytes(condition_id.as_bytes().to_vec()),
+                Token::Uint(amount),
+            ],
+        )
+    }
+
+    /// `redeemPositions`: burns `yes_amount` and `no_amount` of a resolved
+    /// question. Unlike the CTF, the adapter redeems exact amounts, not the
+    /// whole balance.
+    pub fn redeem_positions(
+        &self,
+        condition_id: H256,
+        yes_amount: U256,
+        no_amount: U256,
+    ) -> SafeTransaction {
+        contract_call(
+            self.address,
+            "redeemPositions(bytes32,uint256[])",
+            &[
+                Token::FixedBytes(condition_id.as_bytes().to_vec()),
+                Token::Array(vec![Token::Uint(yes_amount), Token::Uint(no_amount)]),
+            ],
+        )
+    }
+
+    /// `convertPositions`: converts `amount` of NO of each question of
+    /// `market_id` whose index is set in `index_set` into collateral and YES
+    /// of every other question.
+    pub fn convert_positions(
+        &self,
+        market_id: H256,
+        index_set: U256,
+        amount: U256,
+    ) -> Result<SafeTransaction, RelayerClientException> {
+        if index_set.is_zero() {
+            return Err(RelayerClientException::encoding(
+                "index set must select at least one question",
+            ));
+        }
+        Ok(contract_call(
+            self.address,
+            "convertPositions(bytes32,uint256,uint256)",
+            &[
+                Token::FixedBytes(market_id.as_bytes().to_vec()),
+                Token::Uint(index_set),
+                Token::Uint(amount),
+            ],
+        ))
+    }
+}
+
+#[test]
+fn test_neg_risk_calldata() {
+    let adapter =
+        NegRiskAdapter::from_config(&crate::config::get_contract_config(137).unwrap()).unwrap();
+    let condition_id = H256::repeat_byte(0xcd);
+
+    let redeem = adapter.redeem_positions(condition_id, U256::from(5), U256::zero());
+    assert_eq!(redeem.to, adapter.address);
+    assert_eq!(
+        &redeem.data[10..],
+        "cdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcd\
+         0000000000000000000000000000000000000000000000000000000000000040\
+         0000000000000000000000000000000000000000000000000000000000000002\
+         0000000000000000000000000000000000000000000000000000000000000005\
+         0000000000000000000000000000000000000000000000000000000000000000"
+    );
+
+    let split = adapter.split_position(condition_id, U256::from(7));
+    let merge = adapter.merge_positions(condition_id, U256::from(7));
+    assert_eq!(split.data.len(), 2 + 8 + 64 * 2);
+    assert_eq!(split.data[10..], merge.data[10..]);
+    assert_ne!(split.data[..10], merge.data[..10]);
+
+    let convert = adapter
+        .convert_positions(H256::repeat_byte(0x11), U256::from(0b101), U256::from(3))
+        .unwrap();
+    assert!(convert.data.ends_with(
+        "0000000000000000000000000000000000000000000000000000000000000005\
+         0000000000000000000000000000000000000000000000000000000000000003"
+    ));
+    assert!(
+        adapter
+            .convert_positions(H256::zero(), U256::zero(), U256::one())
+            .is_err()
+    );
+    assert!(
+        NegRiskAdapter::from_config(&crate::config::get_contract_config(80002).unwrap()).is_err()
+    );
+}
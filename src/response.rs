@@ -1,5 +1,5 @@
-use crate::models::RelayerTransactionState;
-use serde_json::Value;
+use crate::models::RelayerTransaction;
+use crate::watch::{TransactionOutcome, WatchOptions};
 use std::fmt::{Debug, Error, Formatter};
 
 pub struct ClientRelayerTransactionResponse<'a> {
@@ -24,33 +24,34 @@ impl<'a> ClientRelayerTransactionResponse<'a> {
         }
     }
 
-    pub fn get_transaction(&self) -> Result<Value, crate::errors::RelayerApiException> {
+    pub async fn get_transaction(
+        &self,
+    ) -> Result<Vec<RelayerTransaction>, crate::errors::RelayerApiException> {
         if let Some(ref id) = self.transaction_id {
-            self.client.get_transaction(id)
+            self.client.get_transaction(id).await
         } else {
-            Err(crate::errors::RelayerApiException::new(
-                None,
-                "No transaction ID".to_string(),
-            ))
+            Err(crate::errors::RelayerApiException::MissingTransactionId)
         }
     }
 
-    pub fn wait(&self) -> Result<Option<Value>, crate::errors::RelayerApiException> {
-        if self.transaction_id.is_none() {
-            return Ok(None);
-        }
+    /// Waits up to a minute for the transaction to be mined, fail or be
+    /// invalidated; [`ClientRelayerTransactionResponse::wait_for_outcome`]
+    /// with the default [`WatchOptions`].
+    pub async fn wait(&self) -> Result<TransactionOutcome, crate::errors::RelayerApiException> {
+        self.wait_for_outcome(WatchOptions::default()).await
+    }
+
+    /// Waits for the typed outcome of the transaction, see
+    /// [`crate::RelayClient::watch`].
+    pub async fn wait_for_outcome(
+        &self,
+        options: WatchOptions,
+    ) -> Result<TransactionOutcome, crate::errors::RelayerApiException> {
+        let Some(transaction_id) = self.transaction_id.as_deref() else {
+            return Err(crate::errors::RelayerApiException::MissingTransactionId);
+        };
 
-        let transaction_id = self.transaction_id.as_ref().unwrap().clone();
-        self.client.poll_until_state(
-            &transaction_id,
-            &[
-                RelayerTransactionState::StateMined.as_str(),
-                RelayerTransactionState::StateConfirmed.as_str(),
-            ],
-            Some(RelayerTransactionState::StateFailed.as_str()),
-            Some(30),
-            Some(2000),
-        )
+        self.client.wait_for_outcome(transaction_id, options).await
     }
 }
 
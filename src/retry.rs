@@ -0,0 +1,194 @@
+use crate::errors::RelayerApiException;
+use crate::transport::TransportResponse;
+use chrono::{DateTime, Utc};
+use rand::Rng;
+use reqwest::header::RETRY_AFTER;
+use std::time::Duration;
+
+/// How [`crate::RelayClient`] retries failed relayer requests.
+///
+/// Idempotent requests (GET) are retried on transport errors and on any of
+/// `retry_statuses`. Other requests, such as `/submit`, are only retried
+/// when the relayer provably did not accept them: the connection could not
+/// be made, or it answered `429 Too Many Requests`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Total attempts including the first one; `1` disables retries.
+    pub max_attempts: u32,
+    /// Backoff before the first retry, doubled on every further retry.
+    pub initial_backoff: Duration,
+    /// Upper bound for the computed backoff and for `Retry-After`.
+    pub max_backoff: Duration,
+    /// Randomize each backoff between half and all of its value.
+    pub jitter: bool,
+    /// Status codes worth retrying for idempotent requests.
+    pub retry_statuses: Vec<u16>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(250),
+            max_backoff: Duration::from_secs(10),
+            jitter: true,
+            retry_statuses: vec![429, 500, 502, 503, 504],
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy making a single attempt.
+    pub fn none() -> Self {
+        RetryPolicy {
+            max_attempts: 1,
+            ..RetryPolicy::default()
+        }
+    }
+
+    pub(crate) fn should_retry_status(&self, status: u16, idempotent: bool) -> bool {
+        if idempotent {
+            self.retry_statuses.contains(&status)
+        } else {
+            // A rate-limited request was turned away before being processed
+            status == 429 && self.retry_statuses.contains(&429)
+        }
+    }
+
+    pub(crate) fn should_retry_error(&self, error: &RelayerApiException, idempotent: bool) -> bool {
+        (idempotent && error.is_retryable())
+            || matches!(error, RelayerApiException::ConnectionFailed(_))
+    }
+
+    /// Delay before retry number `retry` (starting at 1), preferring the
+    /// response's `Retry-After` when present.
+    pub(crate) fn delay(&self, retry: u32, response: Option<&TransportResponse>) -> Duration {
+        if let Some(retry_after) = response.and_then(retry_after) {
+            return retry_after.min(self.max_backoff);
+        }
+
+        let factor = 2u32.saturating_pow(retry.saturating_sub(1));
+        let backoff = self
+            .initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff);
+        if self.jitter && !backoff.is_zero() {
+            rand::thread_rng().gen_range(backoff / 2..=backoff)
+        } else {
+            backoff
+        }
+    }
+}
+
+/// Parses `Retry-After` as either delay-seconds or an HTTP date.
+fn retry_after(response: &TransportResponse) -> Option<Duration> {
+    let value = response.headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    (date.with_timezone(&Utc) - Utc::now()).to_std().ok()
+}
+
+#[test]
+fn test_retry_delay() {
+    use reqwest::header::{HeaderMap, HeaderValue};
+
+    let policy = RetryPolicy {
+        jitter: false,
+        ..RetryPolicy::default()
+    };
+    assert_eq!(policy.delay(1, None), Duration::from_millis(250));
+    assert_eq!(policy.delay(3, None), Duration::from_millis(1000));
+    assert_eq!(policy.delay(20, None), Duration::from_secs(10));
+
+    let mut headers = HeaderMap::new();
+    headers.insert(RETRY_AFTER, HeaderValue::from_static("2"));
+    let response = TransportResponse {
+        status: 429,
+        headers,
+        body: String::new(),
+    };
+    assert_eq!(policy.delay(1, Some(&response)), Duration::from_secs(2));
+
+    assert!(policy.should_retry_status(503, true));
+    assert!(!policy.should_retry_status(503, false));
+    assert!(policy.should_retry_status(429, false));
+    assert!(!policy.should_retry_status(400, true));
+}
+
+#[tokio::test]
+async fn test_client_retries_idempotent_requests_only() {
+    use crate::transport::{RelayTransport, TransportRequest};
+    use reqwest::Method;
+    use reqwest::header::HeaderMap;
+    use std::sync::{Arc, Mutex};
+
+    // Fails every request with a 503 until `failures` runs out
+    struct FlakyTransport {
+        failures: Mutex<u32>,
+        methods: Mutex<Vec<Method>>,
+    }
+
+    #[async_trait::async_trait]
+    impl RelayTransport for FlakyTransport {
+        async fn send(
+            &self,
+            request: TransportRequest,
+        ) -> Result<TransportResponse, RelayerApiException> {
+            self.methods.lock().unwrap().push(request.method);
+            let mut failures = self.failures.lock().unwrap();
+            let (status, body) = if *failures > 0 {
+                *failures -= 1;
+                (503, "")
+            } else {
+                (200, r#"{"deployed":true}"#)
+            };
+            Ok(TransportResponse {
+                status,
+                headers: HeaderMap::new(),
+                body: body.to_string(),
+            })
+        }
+    }
+
+    let transport = Arc::new(FlakyTransport {
+        failures: Mutex::new(2),
+        methods: Mutex::new(Vec::new()),
+    });
+    let policy = RetryPolicy {
+        initial_backoff: Duration::ZERO,
+        ..RetryPolicy::default()
+    };
+    let client = crate::RelayClient::new("http://relayer.test", 137, None, None)
+        .unwrap()
+        .with_transport(transport.clone())
+        .with_retry_policy(policy.clone());
+    assert!(
+        client
+            .get_deployed(&ethers::types::Address::zero())
+            .await
+            .unwrap()
+    );
+    assert_eq!(transport.methods.lock().unwrap().len(), 3);
+
+    // A 503 from /submit may have been accepted, so it is not retried
+    *transport.failures.lock().unwrap() = 1;
+    transport.methods.lock().unwrap().clear();
+    let result: Result<serde_json::Value, _> = crate::http_helpers::post(
+        transport.as_ref(),
+        &policy,
+        "http://relayer.test/submit",
+        None,
+        None,
+    )
+    .await;
+    assert!(matches!(
+        result,
+        Err(RelayerApiException::ApiError {
+            status_code: Some(503),
+            ..
+        })
+    ));
+    assert_eq!(transport.methods.lock().unwrap().as_slice(), [Method::POST]);
+}
@@ -14,7 +14,7 @@ pub struct Signer {
 impl Signer {
     pub fn new(private_key: &str, chain_id: u64) -> Result<Self, RelayerClientException> {
         let wallet = LocalWallet::from_str(private_key)
-            .map_err(|e| RelayerClientException::new(format!("Invalid private key: {}", e)))?
+            .map_err(RelayerClientException::Wallet)?
             .with_chain_id(chain_id);
 
         Ok(Signer { wallet, chain_id })
@@ -33,7 +33,7 @@ impl Signer {
         let sig = self
             .wallet
             .sign_hash(H256::from_slice(message_hash))
-            .map_err(|e| RelayerClientException::new(format!("Signing failed: {}", e)))?;
+            .map_err(RelayerClientException::Wallet)?;
         Ok(prepend_zx(&sig.to_string()))
     }
 
@@ -51,7 +51,7 @@ impl Signer {
         let sig = self
             .wallet
             .sign_hash(H256::from_slice(&hash))
-            .map_err(|e| RelayerClientException::new(format!("EIP712 signing failed: {}", e)))?;
+            .map_err(RelayerClientException::Wallet)?;
         Ok(prepend_zx(&sig.to_string()))
     }
 }
@@ -0,0 +1,221 @@
+//! Token amounts with their decimals, and the known ERC-20s of each chain.
+
+use crate::errors::{RelayerClientException, TokenAmountError};
+use ethers::types::{Address, U256};
+use std::collections::HashMap;
+use std::fmt;
+
+/// An amount in base units together with its token's decimals, so that
+/// "1" USDC is 1000000 and not 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TokenAmount {
+    raw: U256,
+    decimals: u8,
+}
+
+impl TokenAmount {
+    pub fn from_raw(raw: U256, decimals: u8) -> Self {
+        TokenAmount { raw, decimals }
+    }
+
+    /// Parses a human amount such as "12.5". Digits beyond `decimals` that
+    /// are not zero are an error rather than being truncated.
+    pub fn parse(amount: &str, decimals: u8) -> Result<Self, TokenAmountError> {
+        let invalid = || TokenAmountError::Invalid(amount.to_string());
+        let (whole, fraction) = amount.split_once('.').unwrap_or((amount, ""));
+        if whole.is_empty()
+            || !whole.bytes().all(|b| b.is_ascii_digit())
+            || !fraction.bytes().all(|b| b.is_ascii_digit())
+        {
+            return Err(invalid());
+        }
+
+        let fraction = fraction.trim_end_matches('0');
+        if fraction.len() > decimals as usize {
+            return Err(TokenAmountError::TooPrecise {
+                amount: amount.to_string(),
+                decimals,
+            });
+        }
+        let digits = format!("{}{:0<width$}", whole, fraction, width = decimals as usize);
+        let raw = U256::from_dec_str(&digits)
+            .map_err(|_| TokenAmountError::Overflow(amount.to_string()))?;
+        Ok(TokenAmount { raw, decimals })
+    }
+
+    /// The amount in base units, as sent on-chain.
+    pub fn raw(&self) -> U256 {
+        self.raw
+    }
+
+    pub fn decimals(&self) -> u8 {
+        self.decimals
+    }
+}
+
+/// The human amount, without trailing zeros.
+impl fmt::Display for TokenAmount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digits = format!(
+            "{:0>width$}",
+            self.raw.to_string(),
+            width = self.decimals as usize + 1
+        );
+        let (whole, fraction) = digits.split_at(digits.len() - self.decimals as usize);
+        let fraction = fraction.trim_end_matches('0');
+        if fraction.is_empty() {
+            write!(f, "{}", whole)
+        } else {
+            write!(f, "{}.{}", whole, fraction)
+        }
+    }
+}
+
+/// An ERC-20 token.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    pub symbol: String,
+    pub address: Address,
+    pub decimals: u8,
+}
+
+impl Token {
+    pub fn new(symbol: impl Into<String>, address: Address, decimals: u8) -> Self {
+        Token {
+            symbol: symbol.into(),
+            address,
+            decimals,
+        }
+    }
+
+    /// Parses a human amount of this token, see [`TokenAmount::parse`].
+    pub fn amount(&self, amount: &str) -> Result<TokenAmount, TokenAmountError> {
+        TokenAmount::parse(amount, self.decimals)
+    }
+
+    pub fn raw_amount(&self, raw: U256) -> TokenAmount {
+        TokenAmount::from_raw(raw, self.decimals)
+    }
+}
+
+lazy_static::lazy_static! {
+    static ref TOKENS: HashMap<u64, Vec<Token>> = {
+        let mut map = HashMap::new();
+        map.insert(
+            137,
+            vec![
+                Token::new(
+                    "USDC.e",
+                    "0x2791Bca1f2de4661ED88A30C99A7a9449Aa84174"
+                        .parse()
+                        .unwrap(),
+                    6,
+                ),
+                Token::new(
+                    "USDC",
+                    "0x3c499c542cEF5E3811e1192ce70d8cC03d5c3359"
+                        .parse()
+                        .unwrap(),
+                    6,
+                ),
+            ],
+        );
+        map.insert(
+            80002,
+            vec![
+                // Polymarket's test collateral
+                Token::new(
+                    "USDC.e",
+                    "0x9c4e1703476e875070ee25b56a58b008cfb8fa78"
+                        .parse()
+                        .unwrap(),
+                    6,
+                ),
+            ],
+        );
+        map
+    };
+}
+
+/// The known tokens of `chain_id`.
+pub fn tokens(chain_id: u64) -> Result<&'static [Token], RelayerClientException> {
+    TOKENS
+        .get(&chain_id)
+        .map(Vec::as_slice)
+        .ok_or(RelayerClientException::UnsupportedChain(chain_id))
+}
+
+/// The token with `symbol` on `chain_id`, e.g. `"USDC.e"`.
+pub fn token(chain_id: u64, symbol: &str) -> Result<Token, RelayerClientException> {
+    tokens(chain_id)?
+        .iter()
+        .find(|token| token.symbol == symbol)
+        .cloned()
+        .ok_or_else(|| RelayerClientException::UnknownToken {
+            chain_id,
+            symbol: symbol.to_string(),
+        })
+}
+
+#[test]
+fn test_token_amounts() {
+    let usdc = token(137, "USDC.e").unwrap();
+    assert_eq!(
+        Some(usdc.address),
+        crate::config::get_contract_config(137).unwrap().collateral
+    );
+
+    for (human, raw) in [
+        ("1", 1_000_000u64),
+        ("12.5", 12_500_000),
+        ("0.000001", 1),
+        ("7.", 7_000_000),
+        ("3.1400000000", 3_140_000),
+    ] {
+        let amount = usdc.amount(human).unwrap();
+        assert_eq!(amount.raw(), U256::from(raw), "{}", human);
+        assert_eq!(amount.decimals(), 6);
+    }
+    assert_eq!(usdc.raw_amount(U256::from(12_500_000)).to_string(), "12.5");
+    assert_eq!(usdc.raw_amount(U256::from(1)).to_string(), "0.000001");
+    assert_eq!(usdc.raw_amount(U256::zero()).to_string(), "0");
+    assert_eq!(TokenAmount::from_raw(U256::from(42), 0).to_string(), "42");
+
+    assert!(matches!(
+        usdc.amount("0.0000001"),
+        Err(TokenAmountError::TooPrecise { decimals: 6, .. })
+    ));
+    for invalid in ["", ".5", "-1", "1e6", " 1", "1.2.3", "1,5"] {
+        assert!(
+            matches!(usdc.amount(invalid), Err(TokenAmountError::Invalid(_))),
+            "{}",
+            invalid
+        );
+    }
+    let max = TokenAmount::from_raw(U256::MAX, 6).to_string();
+    assert_eq!(usdc.amount(&max).unwrap().raw(), U256::MAX);
+    assert!(matches!(
+        TokenAmount::parse(
+            "115792089237316195423570985008687907853269984665640564039457584007913129639936",
+            0
+        ),
+        Err(TokenAmountError::Overflow(_))
+    ));
+    assert!(matches!(
+        usdc.amount("1e999"),
+        Err(TokenAmountError::Invalid(_))
+    ));
+    assert!(matches!(
+        TokenAmount::parse("1", 78),
+        Err(TokenAmountError::Overflow(_))
+    ));
+
+    assert!(matches!(
+        token(137, "DAI"),
+        Err(RelayerClientException::UnknownToken { chain_id: 137, symbol }) if symbol == "DAI"
+    ));
+    assert!(matches!(
+        tokens(1),
+        Err(RelayerClientException::UnsupportedChain(1))
+    ));
+}
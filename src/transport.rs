@@ -0,0 +1,220 @@
+use crate::errors::RelayerApiException;
+use async_trait::async_trait;
+use reqwest::header::HeaderMap;
+use reqwest::{Client, Method};
+use std::sync::Arc;
+use std::time::Duration;
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// An HTTP request as issued by [`crate::RelayClient`].
+#[derive(Debug, Clone)]
+pub struct TransportRequest {
+    pub method: Method,
+    pub url: String,
+    pub headers: HeaderMap,
+    pub body: Option<String>,
+}
+
+/// The raw HTTP response handed back to [`crate::RelayClient`].
+#[derive(Debug, Clone)]
+pub struct TransportResponse {
+    pub status: u16,
+    pub headers: HeaderMap,
+    pub body: String,
+}
+
+/// The HTTP layer used by [`crate::RelayClient`].
+///
+/// Implementations only move bytes: status handling and JSON decoding are
+/// done by the client, so a fake or record/replay transport only needs to
+/// produce a status code and a body.
+#[async_trait]
+pub trait RelayTransport: Send + Sync {
+    async fn send(
+        &self,
+        request: TransportRequest,
+    ) -> Result<TransportResponse, RelayerApiException>;
+}
+
+#[async_trait]
+impl<T: RelayTransport + ?Sized> RelayTransport for Arc<T> {
+    async fn send(
+        &self,
+        request: TransportRequest,
+    ) -> Result<TransportResponse, RelayerApiException> {
+        (**self).send(request).await
+    }
+}
+
+/// Default transport backed by a shared [`reqwest::Client`].
+#[derive(Debug, Clone)]
+pub struct ReqwestTransport {
+    client: Client,
+}
+
+impl ReqwestTransport {
+    pub fn new() -> Result<Self, RelayerApiException> {
+        let client = Client::builder().timeout(TIMEOUT).build().map_err(|e| {
+            RelayerApiException::from_request_error(format!("Failed to create client: {}", e))
+        })?;
+        Ok(ReqwestTransport { client })
+    }
+
+    pub fn from_client(client: Client) -> Self {
+        ReqwestTransport { client }
+    }
+}
+
+#[async_trait]
+impl RelayTransport for ReqwestTransport {
+    async fn send(
+        &self,
+        request: TransportRequest,
+    ) -> Result<TransportResponse, RelayerApiException> {
+        let mut req = self
+            .client
+            .request(request.method, &request.url)
+            .headers(request.headers);
+        if let Some(body) = request.body {
+            req = req.body(body);
+        }
+
+        let resp = req.send().await.map_err(|e| {
+            if e.is_connect() {
+                RelayerApiException::ConnectionFailed(e.to_string())
+            } else if e.is_timeout() {
+                RelayerApiException::Timeout(e.to_string())
+            } else {
+                RelayerApiException::from_request_error(format!("Request failed: {}", e))
+            }
+        })?;
+
+        let status = resp.status().as_u16();
+        let headers = resp.headers().clone();
+        let body = resp.text().await.map_err(|e| {
+            RelayerApiException::from_request_error(format!("Failed to read body: {}", e))
+        })?;
+
+        Ok(TransportResponse {
+            status,
+            headers,
+            body,
+        })
+    }
+}
+
+#[tokio::test]
+async fn test_client_uses_injected_transport() {
+    use std::sync::Mutex;
+
+    struct FakeTransport {
+        urls: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl RelayTransport for FakeTransport {
+        async fn send(
+            &self,
+            request: TransportRequest,
+        ) -> Result<TransportResponse, RelayerApiException> {
+            self.urls.lock().unwrap().push(request.url);
+            Ok(TransportResponse {
+                status: 200,
+                headers: HeaderMap::new(),
+                body: r#"{"deployed":true}"#.to_string(),
+            })
+        }
+    }
+
+    let fake = Arc::new(FakeTransport {
+        urls: Mutex::new(Vec::new()),
+    });
+    let client = crate::RelayClient::new("http://relayer.test/", 137, None, None)
+        .unwrap()
+        .with_transport(fake.clone());
+    let deployed = client
+        .get_deployed(&ethers::types::Address::zero())
+        .await
+        .unwrap();
+
+    assert!(deployed);
+    assert_eq!(
+        fake.urls.lock().unwrap().as_slice(),
+        ["http://relayer.test/deployed?address=0x0000000000000000000000000000000000000000"]
+    );
+}
+
+#[tokio::test]
+async fn test_submit_offline_signed_request() {
+    use crate::models::{OperationType, SafeTransaction, TransactionRequest};
+    use polymarket_client_sdk::auth::Credentials;
+    use polymarket_client_sdk::auth::builder::{Builder, Config};
+    use std::sync::Mutex;
+
+    struct RecordingTransport {
+        bodies: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl RelayTransport for RecordingTransport {
+        async fn send(
+            &self,
+            request: TransportRequest,
+        ) -> Result<TransportResponse, RelayerApiException> {
+            self.bodies
+                .lock()
+                .unwrap()
+                .push(request.body.unwrap_or_default());
+            Ok(TransportResponse {
+                status: 200,
+                headers: HeaderMap::new(),
+                body: r#"{"transactionID":"abc","state":"STATE_NEW"}"#.to_string(),
+            })
+        }
+    }
+
+    // Signed on the offline machine, without builder credentials
+    let offline = crate::RelayClient::new(
+        "http://relayer.test",
+        137,
+        Some("0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80"),
+        None,
+    )
+    .unwrap();
+    let txn = SafeTransaction {
+        to: ethers::types::Address::zero(),
+        operation: OperationType::Call,
+        data: "0x".to_string(),
+        value: "0".to_string(),
+    };
+    let signed = offline
+        .build_execute_request(&[txn], "7", Some("offline"), None)
+        .await
+        .unwrap();
+    let file_contents = serde_json::to_string(&signed).unwrap();
+
+    // Submitted later by a client holding only builder credentials
+    let transport = Arc::new(RecordingTransport {
+        bodies: Mutex::new(Vec::new()),
+    });
+    let builder = Builder::new(
+        Config::Local(Credentials::new(
+            uuid::Uuid::nil(),
+            "c2VjcmV0".to_string(),
+            "passphrase".to_string(),
+        )),
+        Client::new(),
+    );
+    let online = crate::RelayClient::new("http://relayer.test", 137, None, Some(builder))
+        .unwrap()
+        .with_transport(transport.clone());
+    let restored: TransactionRequest = serde_json::from_str(&file_contents).unwrap();
+    let resp = online.submit_signed(&restored).await.unwrap();
+
+    assert_eq!(resp.transaction_id.as_deref(), Some("abc"));
+    let sent: serde_json::Value =
+        serde_json::from_str(&transport.bodies.lock().unwrap()[0]).unwrap();
+    assert_eq!(sent["signature"], signed.signature);
+    assert_eq!(sent["nonce"], "7");
+}
@@ -1,3 +1,6 @@
+use crate::errors::RelayerClientException;
+use ethers::types::U256;
+
 pub fn prepend_zx(s: &str) -> String {
     if s.len() > 2 && !s.starts_with("0x") {
         format!("0x{}", s)
@@ -6,3 +9,24 @@ pub fn prepend_zx(s: &str) -> String {
     }
 }
 
+/// Decodes a `0x`-prefixed (or bare) 32-byte hex hash.
+pub fn hash_from_hex(hash: &str) -> Result<[u8; 32], RelayerClientException> {
+    let hash = hash.strip_prefix("0x").unwrap_or(hash);
+    hex::decode(hash)
+        .map_err(|e| RelayerClientException::encoding(format!("Invalid hash: {}", e)))?
+        .try_into()
+        .map_err(|_| RelayerClientException::encoding("Invalid hash length"))
+}
+
+/// Decodes `0x`-prefixed (or bare) hex, naming the field in the error.
+pub fn decode_hex(data: &str, name: &str) -> Result<Vec<u8>, RelayerClientException> {
+    let data = data.strip_prefix("0x").unwrap_or(data);
+    hex::decode(data)
+        .map_err(|e| RelayerClientException::encoding(format!("Invalid {}: {}", name, e)))
+}
+
+/// Parses a decimal uint256, naming the field in the error.
+pub fn parse_u256(value: &str, name: &str) -> Result<U256, RelayerClientException> {
+    U256::from_dec_str(value)
+        .map_err(|e| RelayerClientException::encoding(format!("Invalid {}: {}", name, e)))
+}
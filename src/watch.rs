@@ -0,0 +1,333 @@
+use crate::client::RelayClient;
+use crate::errors::RelayerApiException;
+use crate::models::{RelayerTransaction, RelayerTransactionState};
+use futures::Stream;
+use futures::stream;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// How [`RelayClient::watch`] follows a transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WatchOptions {
+    /// Give up with [`TransactionOutcome::TimedOut`] after this long.
+    pub deadline: Duration,
+    /// Delay between two `/transaction` polls.
+    pub poll_interval: Duration,
+    /// Keep watching a mined transaction until it is confirmed.
+    pub until_confirmed: bool,
+}
+
+impl Default for WatchOptions {
+    fn default() -> Self {
+        WatchOptions {
+            deadline: Duration::from_secs(60),
+            poll_interval: Duration::from_secs(2),
+            until_confirmed: false,
+        }
+    }
+}
+
+/// How a watched transaction ended.
+#[derive(Debug, Clone)]
+pub enum TransactionOutcome {
+    Mined(RelayerTransaction),
+    Confirmed(RelayerTransaction),
+    /// Reverted onchain; `transaction_hash` is the failed transaction.
+    Failed {
+        transaction_hash: Option<String>,
+        transaction: RelayerTransaction,
+    },
+    /// Rejected by the relayer without being sent onchain.
+    Invalid(RelayerTransaction),
+    /// The deadline passed first; `last` is the latest known state, if the
+    /// relayer reported one.
+    TimedOut {
+        last: Option<RelayerTransaction>,
+    },
+}
+
+impl TransactionOutcome {
+    /// Whether the transaction made it onchain successfully.
+    pub fn is_success(&self) -> bool {
+        matches!(
+            self,
+            TransactionOutcome::Mined(_) | TransactionOutcome::Confirmed(_)
+        )
+    }
+
+    /// The final transaction as reported by the relayer.
+    pub fn transaction(&self) -> Option<&RelayerTransaction> {
+        match self {
+            TransactionOutcome::Mined(txn)
+            | TransactionOutcome::Confirmed(txn)
+            | TransactionOutcome::Failed {
+                transaction: txn, ..
+            }
+            | TransactionOutcome::Invalid(txn) => Some(txn),
+            TransactionOutcome::TimedOut { last } => last.as_ref(),
+        }
+    }
+
+    fn from_transaction(txn: &RelayerTransaction, until_confirmed: bool) -> Option<Self> {
+        match txn.state {
+            RelayerTransactionState::StateMined if !until_confirmed => {
+                Some(TransactionOutcome::Mined(txn.clone()))
+            }
+            RelayerTransactionState::StateConfirmed => {
+                Some(TransactionOutcome::Confirmed(txn.clone()))
+            }
+            RelayerTransactionState::StateFailed => Some(TransactionOutcome::Failed {
+                transaction_hash: txn.transaction_hash.clone(),
+                transaction: txn.clone(),
+            }),
+            RelayerTransactionState::StateInvalid => Some(TransactionOutcome::Invalid(txn.clone())),
+            _ => None,
+        }
+    }
+}
+
+/// An item of [`RelayClient::watch`].
+#[derive(Debug, Clone)]
+pub enum WatchEvent {
+    /// The relayer reported a state different from the previous one.
+    StateChanged(RelayerTransaction),
+    /// The last item of the stream.
+    Finished(TransactionOutcome),
+}
+
+struct Watch<'a> {
+    client: &'a RelayClient,
+    transaction_id: String,
+    options: WatchOptions,
+    deadline: Instant,
+    polled: bool,
+    last: Option<RelayerTransaction>,
+    outcome: Option<TransactionOutcome>,
+    done: bool,
+}
+
+impl Watch<'_> {
+    async fn next_event(&mut self) -> Option<Result<WatchEvent, RelayerApiException>> {
+        if self.done {
+            return None;
+        }
+        if let Some(outcome) = self.outcome.take() {
+            self.done = true;
+            return Some(Ok(WatchEvent::Finished(outcome)));
+        }
+
+        loop {
+            if self.polled {
+                let now = Instant::now();
+                if now >= self.deadline {
+                    self.done = true;
+                    return Some(Ok(WatchEvent::Finished(TransactionOutcome::TimedOut {
+                        last: self.last.take(),
+                    })));
+                }
+                tokio::time::sleep(self.options.poll_interval.min(self.deadline - now)).await;
+            }
+            self.polled = true;
+
+            // A hanging request must not outlive the deadline
+            let remaining = self.deadline.saturating_duration_since(Instant::now());
+            let polled =
+                tokio::time::timeout(remaining, self.client.get_transaction(&self.transaction_id))
+                    .await;
+            let transactions = match polled {
+                Ok(Ok(transactions)) => transactions,
+                Ok(Err(e)) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+                Err(_) => {
+                    self.done = true;
+                    return Some(Ok(WatchEvent::Finished(TransactionOutcome::TimedOut {
+                        last: self.last.take(),
+                    })));
+                }
+            };
+            // The relayer may not list a just-submitted transaction yet
+            let Some(txn) = transactions.into_iter().next() else {
+                continue;
+            };
+            if self
+                .last
+                .as_ref()
+                .is_some_and(|last| last.state == txn.state)
+            {
+                continue;
+            }
+
+            self.outcome = TransactionOutcome::from_transaction(&txn, self.options.until_confirmed);
+            self.last = Some(txn.clone());
+            return Some(Ok(WatchEvent::StateChanged(txn)));
+        }
+    }
+}
+
+pub(crate) fn watch<'a>(
+    client: &'a RelayClient,
+    transaction_id: &str,
+    options: WatchOptions,
+) -> impl Stream<Item = Result<WatchEvent, RelayerApiException>> + Send + 'a {
+    let watch = Watch {
+        client,
+        transaction_id: transaction_id.to_string(),
+        deadline: Instant::now() + options.deadline,
+        options,
+        polled: false,
+        last: None,
+        outcome: None,
+        done: false,
+    };
+    stream::unfold(watch, |mut watch| async move {
+        let event = watch.next_event().await?;
+        Some((event, watch))
+    })
+}
+
+#[tokio::test]
+async fn test_watch_reports_transitions_and_outcome() {
+    use crate::transport::{RelayTransport, TransportRequest, TransportResponse};
+    use futures::StreamExt;
+    use reqwest::header::HeaderMap;
+    use std::sync::Mutex;
+
+    // Replays `states`, repeating the last one forever
+    struct ScriptedTransport {
+        states: Mutex<Vec<&'static str>>,
+    }
+
+    #[async_trait::async_trait]
+    impl RelayTransport for ScriptedTransport {
+        async fn send(
+            &self,
+            _request: TransportRequest,
+        ) -> Result<TransportResponse, RelayerApiException> {
+            let mut states = self.states.lock().unwrap();
+            let state = if states.len() > 1 {
+                states.remove(0)
+            } else {
+                states[0]
+            };
+            Ok(TransportResponse {
+                status: 200,
+                headers: HeaderMap::new(),
+                body: format!(
+                    r#"[{{"transactionID":"tx","state":"{}","transactionHash":"0xabc"}}]"#,
+                    state
+                ),
+            })
+        }
+    }
+
+    let client = |states: Vec<&'static str>| {
+        RelayClient::new("http://relayer.test", 137, None, None)
+            .unwrap()
+            .with_transport(ScriptedTransport {
+                states: Mutex::new(states),
+            })
+    };
+    let options = WatchOptions {
+        deadline: Duration::from_secs(5),
+        poll_interval: Duration::from_millis(1),
+        until_confirmed: false,
+    };
+
+    let mined = client(vec![
+        "STATE_NEW",
+        "STATE_NEW",
+        "STATE_EXECUTED",
+        "STATE_MINED",
+    ]);
+    let events: Vec<_> = mined
+        .watch("tx", options.clone())
+        .map(Result::unwrap)
+        .collect()
+        .await;
+    let states: Vec<_> = events
+        .iter()
+        .filter_map(|event| match event {
+            WatchEvent::StateChanged(txn) => Some(txn.state.clone()),
+            WatchEvent::Finished(_) => None,
+        })
+        .collect();
+    assert_eq!(
+        states,
+        [
+            RelayerTransactionState::StateNew,
+            RelayerTransactionState::StateExecuted,
+            RelayerTransactionState::StateMined,
+        ]
+    );
+    assert!(matches!(
+        events.last(),
+        Some(WatchEvent::Finished(TransactionOutcome::Mined(_)))
+    ));
+
+    let failed = client(vec!["STATE_NEW", "STATE_FAILED"]);
+    let outcome = failed
+        .wait_for_outcome("tx", options.clone())
+        .await
+        .unwrap();
+    assert!(matches!(
+        outcome,
+        TransactionOutcome::Failed { transaction_hash: Some(ref hash), .. } if hash == "0xabc"
+    ));
+
+    let stuck = client(vec!["STATE_MINED"]);
+    let outcome = stuck
+        .wait_for_outcome(
+            "tx",
+            WatchOptions {
+                deadline: Duration::from_millis(20),
+                until_confirmed: true,
+                ..options
+            },
+        )
+        .await
+        .unwrap();
+    assert!(matches!(
+        outcome,
+        TransactionOutcome::TimedOut { last: Some(ref txn) }
+            if txn.state == RelayerTransactionState::StateMined
+    ));
+}
+
+#[tokio::test]
+async fn test_watch_deadline_bounds_a_hanging_poll() {
+    use crate::transport::{RelayTransport, TransportRequest, TransportResponse};
+
+    struct HangingTransport;
+
+    #[async_trait::async_trait]
+    impl RelayTransport for HangingTransport {
+        async fn send(
+            &self,
+            _request: TransportRequest,
+        ) -> Result<TransportResponse, RelayerApiException> {
+            tokio::time::sleep(Duration::from_secs(3600)).await;
+            Err(RelayerApiException::Timeout("never answered".to_string()))
+        }
+    }
+
+    let client = RelayClient::new("http://relayer.test", 137, None, None)
+        .unwrap()
+        .with_transport(HangingTransport);
+    let options = WatchOptions {
+        deadline: Duration::from_millis(50),
+        ..WatchOptions::default()
+    };
+    let outcome = tokio::time::timeout(
+        Duration::from_secs(5),
+        client.wait_for_outcome("tx", options),
+    )
+    .await
+    .expect("the deadline was not enforced")
+    .unwrap();
+    assert!(matches!(
+        outcome,
+        TransactionOutcome::TimedOut { last: None }
+    ));
+}
@@ -0,0 +1,481 @@
+//! End-to-end flows against the in-process mock relayer.
+//!
+//! Run with `cargo test --features test-utils`.
+
+use polymarket_client_sdk::auth::Credentials;
+use polymarket_client_sdk::auth::builder::{Builder, Config};
+use reqwest::Client;
+use rs_builder_relayer_client::config::parse_contract_configs_toml;
+use rs_builder_relayer_client::mock::{MOCK_RELAY, MockCredentials, MockFailure, MockRelayer};
+use rs_builder_relayer_client::polymarket::approvals::{
+    available_trading_approvals_for, trading_approvals_for,
+};
+use rs_builder_relayer_client::{
+    CallType, OperationType, ProxyTransaction, RelayClient, RelayerApiException,
+    RelayerClientException, ReqwestTransport, SafeTransaction, TransactionOutcome, WatchOptions,
+};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+const PK: &str = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+
+const SECRET: &str = "c2VjcmV0";
+const PASSPHRASE: &str = "passphrase";
+
+async fn start_relayer() -> MockRelayer {
+    start_relayer_on(137).await
+}
+
+fn relayer_credentials() -> MockCredentials {
+    MockCredentials::new(uuid::Uuid::nil().to_string(), SECRET, PASSPHRASE)
+}
+
+async fn start_relayer_on(chain_id: u64) -> MockRelayer {
+    MockRelayer::start(chain_id, relayer_credentials())
+        .await
+        .unwrap()
+}
+
+fn builder(secret: &str) -> Builder {
+    Builder::new(
+        Config::Local(Credentials::new(
+            uuid::Uuid::nil(),
+            secret.to_string(),
+            PASSPHRASE.to_string(),
+        )),
+        Client::new(),
+    )
+}
+
+fn client_with_secret(relayer: &MockRelayer, secret: &str) -> RelayClient {
+    RelayClient::new(&relayer.url(), 137, Some(PK), Some(builder(secret))).unwrap()
+}
+
+fn client(relayer: &MockRelayer) -> RelayClient {
+    client_with_secret(relayer, SECRET)
+}
+
+fn options() -> WatchOptions {
+    WatchOptions {
+        deadline: Duration::from_secs(10),
+        poll_interval: Duration::from_millis(10),
+        until_confirmed: false,
+    }
+}
+
+fn approve() -> SafeTransaction {
+    SafeTransaction {
+        to: "0x2791Bca1f2de4661ED88A30C99A7a9449Aa84174"
+            .parse()
+            .unwrap(),
+        operation: OperationType::Call,
+        data: "0x095ea7b3".to_string(),
+        value: "0".to_string(),
+    }
+}
+
+#[tokio::test]
+async fn test_deploy_execute_and_wait() {
+    let relayer = start_relayer().await;
+    let client = client(&relayer);
+    let safe = client.get_expected_safe().unwrap();
+
+    // Executing before deployment fails locally
+    assert!(matches!(
+        client.execute(&[approve()], None, None).await,
+        Err(RelayerClientException::SafeNotDeployed(addr)) if addr == safe
+    ));
+
+    // A deployment whose CreateProxy signature is not the owner's
+    let mut request = client.build_deploy_request().await.unwrap();
+    request.signature_params.payment = Some("1".to_string());
+    let err = client.submit_signed(&request).await.unwrap_err();
+    assert!(matches!(
+        err,
+        RelayerClientException::Api(ref e) if e.status_code() == Some(400)
+    ));
+
+    let deploy = client.deploy().await.unwrap();
+    let outcome = deploy.wait_for_outcome(options()).await.unwrap();
+    assert!(matches!(outcome, TransactionOutcome::Mined(_)));
+    assert!(client.get_deployed(&safe).await.unwrap());
+
+    let resp = client
+        .execute(&[approve()], Some("approve"), None)
+        .await
+        .unwrap();
+    let outcome = resp
+        .wait_for_outcome(WatchOptions {
+            until_confirmed: true,
+            ..options()
+        })
+        .await
+        .unwrap();
+    assert!(matches!(outcome, TransactionOutcome::Confirmed(_)));
+
+    // Later executions take the following nonces from the client's cache
+    client.execute(&[approve()], None, None).await.unwrap();
+    client
+        .execute(&[approve(), approve()], None, None)
+        .await
+        .unwrap();
+    let nonces: Vec<_> = relayer
+        .submissions()
+        .into_iter()
+        .filter_map(|request| request.nonce)
+        .collect();
+    assert_eq!(nonces, ["0", "1", "2"]);
+    assert_eq!(client.get_transactions().await.unwrap().len(), 4);
+}
+
+#[tokio::test]
+async fn test_injected_failures_and_rejections() {
+    let relayer = start_relayer().await;
+    let client = client(&relayer);
+    relayer.set_deployed(client.get_expected_safe().unwrap());
+
+    relayer.fail_next(MockFailure::Failed);
+    let resp = client.execute(&[approve()], None, None).await.unwrap();
+    let outcome = resp.wait_for_outcome(options()).await.unwrap();
+    assert!(matches!(
+        outcome,
+        TransactionOutcome::Failed {
+            transaction_hash: Some(_),
+            ..
+        }
+    ));
+
+    // A nonce conflict is resynced and retried
+    let owner = "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266"
+        .parse()
+        .unwrap();
+    relayer.set_nonce(owner, 5);
+    client.execute(&[approve()], None, None).await.unwrap();
+    assert_eq!(
+        relayer.submissions().last().unwrap().nonce.as_deref(),
+        Some("5")
+    );
+
+    // A tampered signature is rejected
+    let mut request = client
+        .build_execute_request(&[approve()], "6", None, None)
+        .await
+        .unwrap();
+    request.data = "0x095ea7b4".to_string();
+    let err = client.submit_signed(&request).await.unwrap_err();
+    assert!(matches!(
+        err,
+        RelayerClientException::Api(ref e) if e.status_code() == Some(400)
+    ));
+
+    // Submissions without builder headers are refused
+    let status = Client::new()
+        .post(format!("{}/submit", relayer.url()))
+        .body("{}")
+        .send()
+        .await
+        .unwrap()
+        .status();
+    assert_eq!(status, 401);
+}
+
+#[tokio::test]
+async fn test_timeout_after_acceptance_keeps_the_nonce() {
+    let relayer = start_relayer().await;
+    let client = client(&relayer).with_transport(ReqwestTransport::from_client(
+        Client::builder()
+            .timeout(Duration::from_millis(200))
+            .build()
+            .unwrap(),
+    ));
+    relayer.set_deployed(client.get_expected_safe().unwrap());
+
+    // The relayer takes nonce 0 but answers too late
+    relayer.fail_next(MockFailure::Stall(Duration::from_secs(1)));
+    let err = client.execute(&[approve()], None, None).await.unwrap_err();
+    assert!(matches!(
+        err,
+        RelayerClientException::Api(RelayerApiException::Timeout(_))
+    ));
+    assert_eq!(relayer.submissions().len(), 1);
+
+    // Nonce 0 is not handed out again without asking the relayer
+    let owner = "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266"
+        .parse()
+        .unwrap();
+    let fetched = AtomicBool::new(false);
+    let reservation = client
+        .nonce_manager()
+        .reserve(owner, || async {
+            fetched.store(true, Ordering::SeqCst);
+            Ok("1".to_string())
+        })
+        .await
+        .unwrap();
+    assert!(fetched.load(Ordering::SeqCst));
+    assert_eq!(reservation.nonce(), 1);
+    drop(reservation);
+
+    client.execute(&[approve()], None, None).await.unwrap();
+    let nonces: Vec<_> = relayer
+        .submissions()
+        .into_iter()
+        .filter_map(|request| request.nonce)
+        .collect();
+    assert_eq!(nonces, ["0", "1"]);
+}
+
+#[tokio::test]
+async fn test_builder_authentication() {
+    let relayer = start_relayer().await;
+    let client = client(&relayer);
+    relayer.set_deployed(client.get_expected_safe().unwrap());
+
+    // Signed with another secret
+    let err = client_with_secret(&relayer, "b3RoZXI=")
+        .execute(&[approve()], None, None)
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        RelayerClientException::Api(ref e) if e.status_code() == Some(401)
+    ));
+
+    let request = client
+        .build_execute_request(&[approve()], "0", None, None)
+        .await
+        .unwrap();
+    let body = serde_json::to_string(&request).unwrap();
+    let send = |timestamp: i64, signed: &str, sent: &str| {
+        let mut post = Client::new()
+            .post(format!("{}/submit", relayer.url()))
+            .body(sent.to_string());
+        for (name, value) in
+            relayer
+                .credentials()
+                .headers(timestamp, "POST", "/submit", signed.as_bytes())
+        {
+            post = post.header(name, value);
+        }
+        async move { post.send().await.unwrap().status() }
+    };
+    let now = chrono::Utc::now().timestamp();
+
+    // A body other than the one signed, and a stale timestamp
+    let tampered = body.replace("\"nonce\":\"0\"", "\"nonce\":\"1\"");
+    assert_ne!(tampered, body);
+    assert_eq!(send(now, &body, &tampered).await, 401);
+    assert_eq!(send(now - 600, &body, &body).await, 401);
+    assert!(relayer.submissions().is_empty());
+
+    assert_eq!(send(now, &body, &body).await, 200);
+    assert_eq!(relayer.submissions().len(), 1);
+}
+
+#[tokio::test]
+async fn test_example_approvals_on_amoy() {
+    let relayer = start_relayer_on(80002).await;
+    let client = RelayClient::new(&relayer.url(), 80002, Some(PK), Some(builder(SECRET))).unwrap();
+    relayer.set_deployed(client.get_expected_safe().unwrap());
+
+    // The full set needs NegRisk contracts Amoy does not have
+    assert!(matches!(
+        client.setup_trading_approvals().await,
+        Err(RelayerClientException::InvalidConfig(_))
+    ));
+
+    // What examples/execute.rs sends
+    let approvals = available_trading_approvals_for(client.contract_config()).unwrap();
+    assert_eq!(approvals.len(), 2);
+    let resp = client
+        .execute(&approvals, Some("setup trading approvals"), None)
+        .await
+        .unwrap();
+    let outcome = resp.wait_for_outcome(options()).await.unwrap();
+    assert!(matches!(outcome, TransactionOutcome::Mined(_)));
+    assert_eq!(relayer.submissions().len(), 1);
+}
+
+#[tokio::test]
+async fn test_out_of_sequence_nonces() {
+    let relayer = start_relayer().await;
+    let client = client(&relayer);
+    relayer.set_deployed(client.get_expected_safe().unwrap());
+    let owner = "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266"
+        .parse()
+        .unwrap();
+
+    // A nonce ahead of the relayer's is refused, not queued
+    let request = client
+        .build_execute_request(&[approve()], "1", None, None)
+        .await
+        .unwrap();
+    let err = client.submit_signed(&request).await.unwrap_err();
+    assert!(matches!(
+        err,
+        RelayerClientException::Api(ref e) if e.status_code() == Some(400)
+    ));
+    assert!(relayer.submissions().is_empty());
+
+    client.execute(&[approve()], None, None).await.unwrap();
+
+    // The relayer falls back to nonce 0, e.g. after dropping the first
+    // transaction, so the cached nonce 1 is ahead: execute resyncs and retries
+    relayer.set_nonce(owner, 0);
+    client.execute(&[approve()], None, None).await.unwrap();
+    let nonces: Vec<_> = relayer
+        .submissions()
+        .into_iter()
+        .filter_map(|request| request.nonce)
+        .collect();
+    assert_eq!(nonces, ["0", "0"]);
+}
+
+#[tokio::test]
+async fn test_execute_proxy() {
+    let relayer = start_relayer().await;
+    let client = client(&relayer);
+    let owner = "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266"
+        .parse()
+        .unwrap();
+    let transfer = || ProxyTransaction {
+        to: "0x2791Bca1f2de4661ED88A30C99A7a9449Aa84174"
+            .parse()
+            .unwrap(),
+        type_code: CallType::Call,
+        data: "0x095ea7b3".to_string(),
+        value: "0".to_string(),
+    };
+
+    // No deployment is needed for a proxy wallet
+    let resp = client
+        .execute_proxy(&[transfer()], Some("proxy"))
+        .await
+        .unwrap();
+    let outcome = resp.wait_for_outcome(options()).await.unwrap();
+    assert!(matches!(outcome, TransactionOutcome::Mined(_)));
+    client.execute_proxy(&[transfer()], None).await.unwrap();
+
+    let submissions = relayer.submissions();
+    assert_eq!(submissions.len(), 2);
+    assert_eq!(submissions[0].transaction_type, "PROXY");
+    assert_eq!(
+        submissions[0]
+            .proxy
+            .parse::<ethers::types::Address>()
+            .unwrap(),
+        client.get_expected_proxy_wallet().unwrap()
+    );
+    assert_eq!(
+        submissions
+            .iter()
+            .filter_map(|request| request.nonce.as_deref())
+            .collect::<Vec<_>>(),
+        ["0", "1"]
+    );
+
+    let payload = client.get_relay_payload(&owner, "PROXY").await.unwrap();
+    assert_eq!(payload.nonce, "2");
+    assert_eq!(
+        payload.address.parse::<ethers::types::Address>().unwrap(),
+        MOCK_RELAY
+    );
+
+    // A tampered request, and one signed for a used relay nonce
+    let mut request = client
+        .build_proxy_request(&[transfer()], &payload, None)
+        .await
+        .unwrap();
+    request.data.push_str("00");
+    let err = client.submit_signed(&request).await.unwrap_err();
+    assert!(matches!(
+        err,
+        RelayerClientException::Api(ref e) if e.status_code() == Some(400)
+    ));
+    let stale = rs_builder_relayer_client::RelayPayload {
+        nonce: "1".to_string(),
+        ..payload
+    };
+    let request = client
+        .build_proxy_request(&[transfer()], &stale, None)
+        .await
+        .unwrap();
+    let err = client.submit_signed(&request).await.unwrap_err();
+    assert!(matches!(
+        err,
+        RelayerClientException::Api(RelayerApiException::ApiError {
+            relayer_error: Some(ref e),
+            ..
+        }) if e.contains("nonce")
+    ));
+    assert_eq!(relayer.submissions().len(), 2);
+}
+
+#[tokio::test]
+async fn test_loaded_contract_config() {
+    // A devnet described the way `load_contract_configs` reads it, reusing
+    // the Polygon addresses under another chain id and factory name
+    let toml = r#"
+        [31337]
+        safe_factory = "0xaacFeEa03eb1561C4e67d661e40682Bd20E3541b"
+        safe_multisend = "0xA238CBeb142c10Ef7Ad8442C6D1f9E89e07e7761"
+        safe_factory_name = "Devnet Proxy Factory"
+        collateral = "0x2791Bca1f2de4661ED88A30C99A7a9449Aa84174"
+        conditional_tokens = "0x4D97DCd97eC945f40cF65F87097ACe5EA0476045"
+        ctf_exchange = "0x4bFb41d5B3570DeFd03C39a9A4D8dE6Bd8B8982E"
+        neg_risk_ctf_exchange = "0xC5d563A36AE78145C45a50134d48A1215220f80a"
+        neg_risk_adapter = "0xd91E80cF2E7be2e162c6513ceD06f1dD0dA35296"
+    "#;
+    let config = parse_contract_configs_toml(toml)
+        .unwrap()
+        .remove(&31337)
+        .unwrap();
+    assert!(
+        MockRelayer::start(31337, relayer_credentials())
+            .await
+            .is_err()
+    );
+
+    let relayer =
+        MockRelayer::start_with_contract_config(31337, config.clone(), relayer_credentials())
+            .await
+            .unwrap();
+    let client = RelayClient::new_with_contract_config(
+        &relayer.url(),
+        31337,
+        config,
+        Some(PK),
+        Some(builder(SECRET)),
+    )
+    .unwrap();
+
+    // The deployment is signed for the devnet factory name, which the relayer
+    // checks against the same config
+    let outcome = client
+        .deploy()
+        .await
+        .unwrap()
+        .wait_for_outcome(options())
+        .await
+        .unwrap();
+    assert!(matches!(outcome, TransactionOutcome::Mined(_)));
+
+    let approvals = trading_approvals_for(client.contract_config()).unwrap();
+    assert_eq!(approvals.len(), 6);
+    let outcome = client
+        .setup_trading_approvals()
+        .await
+        .unwrap()
+        .wait_for_outcome(options())
+        .await
+        .unwrap();
+    assert!(matches!(outcome, TransactionOutcome::Mined(_)));
+    assert_eq!(
+        relayer.submissions()[1].data,
+        client
+            .build_execute_request(&approvals, "1", None, None)
+            .await
+            .unwrap()
+            .data
+    );
+}